@@ -0,0 +1,383 @@
+//! This module contains the implementation of the sound effects and the music.
+//! The sounds are loaded from the wav files in `assets/sounds/` and played with rodio.
+//! When there is no audio device, or a file is missing, the game stays silent
+//! instead of failing.
+
+use bevy::prelude::*;
+use rodio::{buffer::SamplesBuffer, OutputStream, OutputStreamHandle, Sink, Source};
+use std::{collections::HashMap, fs};
+
+use crate::{
+    common::{asset_path, GameEvent, Settings},
+    score::GameStats,
+};
+
+/// This plugin builds the audio into the app.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_thread_local_resource::<AudioOutput>()
+            .init_resource::<Sounds>()
+            .init_resource::<SoundQueue>()
+            .init_resource::<SoundListener>()
+            .add_system(queue_game_sounds.system())
+            .add_system_to_stage(stage::POST_UPDATE, play_sounds.thread_local_system());
+    }
+}
+
+/// The sound effects of the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Sound {
+    Slide,
+    Merge,
+    Spawn,
+    /// A move that didn't move any tile.
+    Blocked,
+    Highscore,
+    Win,
+    GameOver,
+}
+
+/// All the sound effects, to load them.
+const SOUNDS: [Sound; 7] = [
+    Sound::Slide,
+    Sound::Merge,
+    Sound::Spawn,
+    Sound::Blocked,
+    Sound::Highscore,
+    Sound::Win,
+    Sound::GameOver,
+];
+
+/// The file of the music, it's played in a loop.
+const MUSIC_FILE: &str = "music.wav";
+
+impl Sound {
+    /// Returns the name of the sound's file in `assets/sounds`.
+    fn file(self) -> &'static str {
+        match self {
+            Self::Slide => "slide.wav",
+            Self::Merge => "merge.wav",
+            Self::Spawn => "spawn.wav",
+            Self::Blocked => "blocked.wav",
+            Self::Highscore => "highscore.wav",
+            Self::Win => "win.wav",
+            Self::GameOver => "game_over.wav",
+        }
+    }
+}
+
+/// The samples of a sound, decoded from its file.
+#[derive(Debug, Clone)]
+struct Clip {
+    channels: u16,
+    sample_rate: u32,
+    samples: Vec<i16>,
+}
+
+impl Clip {
+    /// Loads a sound from `assets/sounds`.
+    fn load(file: &str) -> Result<Self, String> {
+        let bytes = fs::read(asset_path("sounds", file)).map_err(|e| e.to_string())?;
+        Self::from_wav(&bytes)
+    }
+
+    /// Decodes a wav file with 16 bits samples.
+    fn from_wav(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err("Not a wav file".to_string());
+        }
+
+        let mut format = None;
+        let mut chunks = &bytes[12..];
+        while chunks.len() >= 8 {
+            let id = &chunks[0..4];
+            let size = u32::from_le_bytes([chunks[4], chunks[5], chunks[6], chunks[7]]) as usize;
+            let data = chunks
+                .get(8..8 + size)
+                .ok_or_else(|| "The file is cut short".to_string())?;
+
+            match id {
+                b"fmt " if data.len() >= 16 => {
+                    let encoding = u16::from_le_bytes([data[0], data[1]]);
+                    let channels = u16::from_le_bytes([data[2], data[3]]);
+                    let sample_rate = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+                    let bits = u16::from_le_bytes([data[14], data[15]]);
+                    if encoding != 1 || bits != 16 || channels == 0 || sample_rate == 0 {
+                        return Err("Only 16 bits PCM is supported".to_string());
+                    }
+                    format = Some((channels, sample_rate));
+                }
+                b"data" => {
+                    let (channels, sample_rate) =
+                        format.ok_or_else(|| "The format is missing".to_string())?;
+                    return Ok(Self {
+                        channels,
+                        sample_rate,
+                        samples: data
+                            .chunks_exact(2)
+                            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                            .collect(),
+                    });
+                }
+                _ => {}
+            }
+
+            // The chunks are padded to an even size.
+            chunks = &chunks[(8 + size + size % 2).min(chunks.len())..];
+        }
+
+        Err("The samples are missing".to_string())
+    }
+
+    /// Creates a source that plays the sound.
+    fn source(&self) -> SamplesBuffer<i16> {
+        SamplesBuffer::new(self.channels, self.sample_rate, self.samples.clone())
+    }
+}
+
+/// This struct saves the sounds, a sound that failed to load is silent.
+pub struct Sounds {
+    effects: HashMap<Sound, Clip>,
+    music: Option<Clip>,
+}
+
+impl FromResources for Sounds {
+    fn from_resources(_resources: &Resources) -> Self {
+        let mut effects = HashMap::new();
+        for &sound in SOUNDS.iter() {
+            match Clip::load(sound.file()) {
+                Ok(clip) => {
+                    effects.insert(sound, clip);
+                }
+                Err(e) => eprintln!("Couldn't load the sound `{}`: {}", sound.file(), e),
+            }
+        }
+
+        let music = match Clip::load(MUSIC_FILE) {
+            Ok(clip) => Some(clip),
+            Err(e) => {
+                eprintln!("Couldn't load the music `{}`: {}", MUSIC_FILE, e);
+                None
+            }
+        };
+
+        Self { effects, music }
+    }
+}
+
+/// This struct saves the sounds that should be played at the end of the frame,
+/// with the speed they are played at. A faster sound has a higher pitch.
+#[derive(Debug, Default)]
+pub struct SoundQueue {
+    sounds: Vec<(Sound, f32)>,
+}
+
+impl SoundQueue {
+    pub fn play(&mut self, sound: Sound) {
+        self.play_at(sound, 1.0);
+    }
+
+    pub fn play_at(&mut self, sound: Sound, speed: f32) {
+        self.sounds.push((sound, speed));
+    }
+}
+
+/// The audio device and the music's sink, `None` when there is no audio device.
+/// The device can't be sent between threads, so this is a thread local resource.
+pub struct AudioOutput {
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    music: Option<Sink>,
+    /// Tells if the music couldn't be played, so it isn't tried again.
+    music_failed: bool,
+}
+
+impl FromResources for AudioOutput {
+    fn from_resources(_resources: &Resources) -> Self {
+        let output = match OutputStream::try_default() {
+            Ok(output) => Some(output),
+            Err(e) => {
+                eprintln!("Couldn't open an audio device, the game is silent: {}", e);
+                None
+            }
+        };
+
+        Self {
+            output,
+            music: None,
+            music_failed: false,
+        }
+    }
+}
+
+/// Event listener for GameEvent.
+#[derive(Default)]
+pub struct SoundListener {
+    pub reader: EventReader<GameEvent>,
+    /// Tells if the new highscore has been played in this game.
+    highscore_played: bool,
+}
+
+/// Returns the speed of a merge's sound, it rises 2 semitones with each level up to 2 octaves.
+fn merge_speed(level: u32) -> f32 {
+    let semitones = (level.saturating_sub(1) * 2).min(24);
+    2f32.powf(semitones as f32 / 12.0)
+}
+
+/// This system chooses the sounds of the game's events.
+pub fn queue_game_sounds(
+    mut listener: ResMut<SoundListener>,
+    game_events: Res<Events<GameEvent>>,
+    stats: Res<GameStats>,
+    mut queue: ResMut<SoundQueue>,
+) {
+    let mut highscore_played = listener.highscore_played;
+    // The highest merge of a move is played, so the merges don't play on top of each other.
+    let mut merge_level = None;
+
+    for event in listener.reader.iter(&game_events) {
+        match *event {
+            GameEvent::Slid { .. } => queue.play(Sound::Slide),
+            GameEvent::Blocked { .. } => queue.play(Sound::Blocked),
+            GameEvent::Spawned { .. } => queue.play(Sound::Spawn),
+            GameEvent::Merged {
+                level: Some(level), ..
+            } => merge_level = merge_level.max(Some(level)),
+            GameEvent::Merged { level: None, .. } => {}
+            // The first game has no highscore to beat.
+            GameEvent::ScoreChanged { score } => {
+                if !highscore_played
+                    && stats.previous_highscore > 0
+                    && stats.is_new_highscore(score)
+                {
+                    highscore_played = true;
+                    queue.play(Sound::Highscore);
+                }
+            }
+            GameEvent::Won => queue.play(Sound::Win),
+            GameEvent::GameOver => queue.play(Sound::GameOver),
+            GameEvent::NewGame => highscore_played = false,
+        }
+    }
+
+    if let Some(level) = merge_level {
+        queue.play_at(Sound::Merge, merge_speed(level));
+    }
+    listener.highscore_played = highscore_played;
+}
+
+/// This system plays the queued sounds, and starts the music and sets its volume.
+/// The sounds are dropped when there is no audio device.
+pub fn play_sounds(_world: &mut World, resources: &mut Resources) {
+    let mut audio_output = resources.get_thread_local_mut::<AudioOutput>().unwrap();
+    let mut queue = resources.get_mut::<SoundQueue>().unwrap();
+    let sounds = resources.get::<Sounds>().unwrap();
+    let settings = resources.get::<Settings>().unwrap();
+
+    let queued = std::mem::take(&mut queue.sounds);
+    let AudioOutput {
+        output,
+        music,
+        music_failed,
+    } = &mut *audio_output;
+    let handle = match output {
+        Some((_, handle)) => handle,
+        None => return,
+    };
+
+    let volume = settings.effective_sfx_volume();
+    if volume > 0.0 {
+        for (sound, speed) in queued {
+            if let Some(clip) = sounds.effects.get(&sound) {
+                let source = clip.source().convert_samples().speed(speed).amplify(volume);
+                if let Err(e) = handle.play_raw(source) {
+                    eprintln!("Couldn't play the sound `{}`: {}", sound.file(), e);
+                }
+            }
+        }
+    }
+
+    // The music starts the first time it can be heard.
+    let music_volume = settings.effective_music_volume();
+    if music.is_none() && !*music_failed && music_volume > 0.0 {
+        if let Some(clip) = &sounds.music {
+            match Sink::try_new(handle) {
+                Ok(sink) => {
+                    sink.append(clip.source().repeat_infinite());
+                    *music = Some(sink);
+                }
+                Err(e) => {
+                    eprintln!("Couldn't play the music: {}", e);
+                    *music_failed = true;
+                }
+            }
+        }
+    }
+    if let Some(sink) = music {
+        if (sink.volume() - music_volume).abs() > f32::EPSILON {
+            sink.set_volume(music_volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a wav file with a format chunk and a data chunk that claims `data_size` bytes.
+    fn wav(encoding: u16, samples: &[i16], data_size: u32) -> Vec<u8> {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&encoding.to_le_bytes());
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&44100u32.to_le_bytes());
+        fmt.extend_from_slice(&(44100u32 * 2).to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+
+        let mut chunks = Vec::new();
+        chunks.extend_from_slice(b"fmt ");
+        chunks.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        chunks.extend_from_slice(&fmt);
+        chunks.extend_from_slice(b"data");
+        chunks.extend_from_slice(&data_size.to_le_bytes());
+        for sample in samples {
+            chunks.extend_from_slice(&sample.to_le_bytes());
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(4 + chunks.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(&chunks);
+        bytes
+    }
+
+    #[test]
+    fn pcm_file_is_decoded() {
+        let clip = Clip::from_wav(&wav(1, &[0, 1000, -1000], 6)).unwrap();
+        assert_eq!(clip.channels, 1);
+        assert_eq!(clip.sample_rate, 44100);
+        assert_eq!(clip.samples, vec![0, 1000, -1000]);
+    }
+
+    #[test]
+    fn truncated_file_is_an_error() {
+        let bytes = wav(1, &[0, 1000, -1000], 6);
+        for len in 0..bytes.len() {
+            assert!(Clip::from_wav(&bytes[..len]).is_err(), "cut at {}", len);
+        }
+    }
+
+    #[test]
+    fn other_encodings_are_an_error() {
+        // 3 is the tag of floating point samples.
+        assert!(Clip::from_wav(&wav(3, &[0, 1000], 4)).is_err());
+    }
+
+    #[test]
+    fn data_longer_than_the_file_is_an_error() {
+        assert!(Clip::from_wav(&wav(1, &[0, 1000], 1000)).is_err());
+        assert!(Clip::from_wav(&wav(1, &[0, 1000], u32::MAX)).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::common::{GameSize, Position};
+use crate::{
+    common::{GameSize, Position, MAX_CELLS},
+    theme::ThemeMaterials,
+};
 
 /// An identifier for the board background's entity.
 pub struct Board;
@@ -11,26 +14,28 @@ pub struct EmptyTile;
 /// This system spawns the board and the emtpy-tiles.
 pub fn spawn_board(
     mut commands: Commands,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    theme_materials: Res<ThemeMaterials>,
     game_size: Res<GameSize>,
 ) {
     // Board background.
     commands
         .spawn(SpriteComponents {
-            material: materials.add(Color::rgb_u8(119, 110, 101).into()),
+            material: theme_materials.board.clone(),
             sprite: Sprite::new(Vec2::new(game_size.board_size(), game_size.board_size())),
             ..Default::default()
         })
         .with(Board);
 
-    // Creating a grid of empty tiles.
-    for row in 0..4 {
-        for col in 0..4 {
+    // Creating a grid of empty tiles for the largest board.
+    // The tiles are drawn in the order they were spawned, so the grid
+    // is spawned once before any tile and is only hidden on smaller boards.
+    for row in 0..MAX_CELLS {
+        for col in 0..MAX_CELLS {
             let position = Position { row, col };
 
             commands
                 .spawn(SpriteComponents {
-                    material: materials.add(Color::rgba_u8(238, 228, 218, 90).into()),
+                    material: theme_materials.empty_cell.clone(),
                     sprite: Sprite::new(Vec2::new(game_size.tile_size(), game_size.tile_size())),
                     transform: Transform::from_translation(position.to_vec3(*game_size)),
                     ..Default::default()
@@ -40,3 +45,17 @@ pub fn spawn_board(
         }
     }
 }
+
+/// This system shows the empty-tiles that are on the board and hides the others.
+pub fn show_empty_tiles(
+    game_size: Res<GameSize>,
+    mut empty_tiles: Query<With<EmptyTile, (&Position, &mut Draw)>>,
+) {
+    let cells = game_size.cells();
+    for (position, mut draw) in empty_tiles.iter_mut() {
+        let visible = position.row < cells && position.col < cells;
+        if draw.is_visible != visible {
+            draw.is_visible = visible;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! This module contains the implementation of the external bot,
+//! a process that plays the game through a line-based JSON protocol
+//! on its stdin and stdout.
+
+mod process;
+mod protocol;
+
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+use crate::{
+    common::{AppState, GameRules, GameSize, GameState, Position, Tile},
+    locale::{Locale, LocalizedText},
+    movement::{MovingDirection, MovingInputEvent, MovingState},
+    score::Score,
+    ui::ToastEvent,
+};
+use process::BotProcess;
+use protocol::{BotReply, BotRequest};
+
+/// How long the bot has to reply before the state is sent again.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// This plugin launches the bot and lets it play.
+pub struct BotPlugin(pub String);
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        match BotProcess::launch(&self.0) {
+            Ok(process) => {
+                app.add_resource(Bot {
+                    process,
+                    waiting: None,
+                    last_id: 0,
+                    exited: false,
+                })
+                .add_system(drive_bot.system());
+            }
+            Err(e) => eprintln!("Couldn't launch the bot `{}`: {}", self.0, e),
+        }
+    }
+}
+
+/// This struct saves the bot's process and the request it should reply to.
+pub struct Bot {
+    process: BotProcess,
+    waiting: Option<Waiting>,
+    /// The number of the last request that was sent.
+    last_id: u64,
+    exited: bool,
+}
+
+/// A request that was sent to the bot and wasn't answered yet.
+struct Waiting {
+    id: u64,
+    since: Instant,
+    legal_moves: Vec<MovingDirection>,
+}
+
+/// While the moving state is `Idle`, sending the board to the bot
+/// and feeding its reply into the moving input.
+/// Timeouts and invalid replies are reported with a toast, and the
+/// board is sent again with a new id. Replies to older requests are ignored.
+#[allow(clippy::too_many_arguments)]
+pub fn drive_bot(
+    mut bot: ResMut<Bot>,
+    app_state: Res<AppState>,
+    game_state: Res<GameState>,
+    moving_state: Res<MovingState>,
+    score: Res<Score>,
+    game_size: Res<GameSize>,
+    rules: Res<GameRules>,
+    mut moving_inputs: ResMut<Events<MovingInputEvent>>,
+    locale: Res<Locale>,
+    mut toasts: ResMut<Events<ToastEvent>>,
+    tiles: Query<(&Tile, &Position)>,
+) {
+    if bot.exited {
+        return;
+    }
+
+    let playing = *app_state == AppState::Playing
+        && matches!(*game_state, GameState::Play)
+        && matches!(*moving_state, MovingState::Idle);
+
+    while let Some(waiting) = &bot.waiting {
+        match bot.process.try_recv() {
+            Ok(Some(line)) => {
+                let reply = serde_json::from_str::<BotReply>(&line);
+                match reply {
+                    // A late reply to a request that was sent again.
+                    Ok(reply) if reply.id != waiting.id => continue,
+                    // The game isn't waiting for a move anymore, e.g. it is over.
+                    Ok(_) if !playing => (),
+                    Ok(reply) if waiting.legal_moves.contains(&reply.direction) => {
+                        moving_inputs.send(MovingInputEvent {
+                            direction: reply.direction,
+                        });
+                    }
+                    Ok(reply) => toasts.send(ToastEvent::new(
+                        locale.render(
+                            &LocalizedText::new("bot-illegal-move")
+                                .with_text("direction", format!("{:?}", reply.direction)),
+                        ),
+                    )),
+                    Err(e) => toasts.send(ToastEvent::new(locale.render(
+                        &LocalizedText::new("bot-invalid-reply").with_text("error", e.to_string()),
+                    ))),
+                }
+                bot.waiting = None;
+            }
+            Ok(None) => {
+                if waiting.since.elapsed() > REPLY_TIMEOUT {
+                    bot.waiting = None;
+                    toasts.send(ToastEvent::new(locale.text("bot-timeout")));
+                }
+                break;
+            }
+            Err(_) => {
+                bot.exited = true;
+                toasts.send(ToastEvent::new(locale.text("bot-exited")));
+                return;
+            }
+        }
+    }
+
+    if bot.waiting.is_some() || !playing {
+        return;
+    }
+
+    // The request is built only when it's sent, finding the legal moves runs the rules.
+    let id = bot.last_id + 1;
+    let request = BotRequest::new(id, game_size.cells(), tiles.iter(), score.0, &rules);
+    if request.legal_moves.is_empty() {
+        return;
+    }
+
+    let line = serde_json::to_string(&request).expect("Bot requests are always serializable");
+    match bot.process.send(&line) {
+        Ok(()) => {
+            bot.last_id = id;
+            bot.waiting = Some(Waiting {
+                id,
+                since: Instant::now(),
+                legal_moves: request.legal_moves,
+            });
+        }
+        Err(e) => {
+            bot.exited = true;
+            toasts.send(ToastEvent::new(locale.render(
+                &LocalizedText::new("bot-unreachable").with_text("error", e.to_string()),
+            )));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! This module contains the implementation of the bot's process.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Mutex,
+    },
+    thread,
+};
+
+/// The bot's process, with its stdin and the lines it has written to its stdout.
+pub struct BotProcess {
+    child: Mutex<Child>,
+    stdin: Mutex<ChildStdin>,
+    replies: Mutex<Receiver<String>>,
+}
+
+impl BotProcess {
+    /// Launches the command through the system's shell.
+    pub fn launch(command: &str) -> io::Result<Self> {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+
+        let mut child = Command::new(shell)
+            .args([flag, command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        // Both are piped, so they always exist.
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // Reading the replies on a background thread so the game won't block.
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            replies: Mutex::new(replies),
+        })
+    }
+
+    /// Writes a single line to the bot.
+    pub fn send(&self, line: &str) -> io::Result<()> {
+        let mut stdin = self.stdin.lock().unwrap();
+        writeln!(stdin, "{}", line)?;
+        stdin.flush()
+    }
+
+    /// Returns the next line written by the bot if there is one.
+    /// Returns an error if the bot has closed its stdout.
+    pub fn try_recv(&self) -> Result<Option<String>, TryRecvError> {
+        match self.replies.lock().unwrap().try_recv() {
+            Ok(line) => Ok(Some(line)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Drop for BotProcess {
+    /// Kills the bot when the game is closed.
+    fn drop(&mut self) {
+        let _ = self.child.lock().unwrap().kill();
+    }
+}
@@ -0,0 +1,62 @@
+//! This module contains the messages exchanged with the bot.
+
+use bevy_2048::rules;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{rules_board, GameRules, Position, Tile},
+    movement::MovingDirection,
+};
+
+/// The state of the game, sent to the bot whenever it should choose a direction.
+#[derive(Debug, Serialize)]
+pub struct BotRequest {
+    /// The number of the request, the reply should echo it.
+    pub id: u64,
+    /// The tiles' values, the first row is the top row and 0 is an empty cell.
+    pub board: Vec<Vec<u32>>,
+    pub score: u32,
+    pub legal_moves: Vec<MovingDirection>,
+}
+
+impl BotRequest {
+    /// Creates a request from the tiles on the board.
+    pub fn new<'a>(
+        id: u64,
+        cells: usize,
+        tiles: impl Iterator<Item = (&'a Tile, &'a Position)>,
+        score: u32,
+        rules: &GameRules,
+    ) -> Self {
+        let rules_board = rules_board(cells, tiles);
+
+        let mut board = vec![vec![0; cells]; cells];
+        for (row, values) in board.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                // Rows are counted from the bottom of the board.
+                if let Some(level) = rules_board.get(cells - 1 - row, col) {
+                    *value = rules::tile_score(level);
+                }
+            }
+        }
+
+        Self {
+            id,
+            board,
+            score,
+            legal_moves: rules_board
+                .legal_moves_with(&**rules)
+                .into_iter()
+                .map(MovingDirection::from)
+                .collect(),
+        }
+    }
+}
+
+/// The reply of the bot.
+#[derive(Debug, Deserialize)]
+pub struct BotReply {
+    /// The number of the request the bot replies to.
+    pub id: u64,
+    pub direction: MovingDirection,
+}
@@ -0,0 +1,84 @@
+//! This module contains the parsing of the command line arguments.
+
+use std::env;
+
+use crate::streaming::StreamAddress;
+
+const USAGE: &str = r#"Usage: bevy_2048 [OPTIONS]
+
+Options:
+    --stream <address>      Publish the game to spectators on <address>.
+    --spectate <address>    Watch a game streamed on <address>.
+    --bot-pipe <command>    Let the bot launched by <command> play the game.
+    --mode <name>           Play the mode in `assets/modes/<name>.rhai`.
+    -h, --help              Print this message.
+
+Addresses are either `tcp:<host>:<port>`, `<host>:<port>` or `unix:<path>`."#;
+
+/// The options the game has been launched with.
+#[derive(Debug, Default)]
+pub struct LaunchOptions {
+    /// Address to publish the game on.
+    pub stream: Option<StreamAddress>,
+    /// Address of a streamed game to watch.
+    pub spectate: Option<StreamAddress>,
+    /// Command that launches a bot.
+    pub bot_pipe: Option<String>,
+    /// Name of the mode's script.
+    pub mode: Option<String>,
+}
+
+impl LaunchOptions {
+    /// Parses the launch options from the command line arguments.
+    /// Prints the usage and exits if the arguments are invalid.
+    pub fn from_args() -> Self {
+        match Self::parse(env::args().skip(1)) {
+            Ok(options) => options,
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--stream" => options.stream = Some(parse_address(&arg, args.next())?),
+                "--spectate" => options.spectate = Some(parse_address(&arg, args.next())?),
+                "--bot-pipe" => {
+                    let command = args.next().ok_or("Missing command for `--bot-pipe`")?;
+                    options.bot_pipe = Some(command);
+                }
+                "--mode" => {
+                    let name = args.next().ok_or("Missing name for `--mode`")?;
+                    options.mode = Some(name);
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("Unknown argument `{}`", arg)),
+            }
+        }
+
+        if options.stream.is_some() && options.spectate.is_some() {
+            return Err("Can't stream and spectate at the same time".to_string());
+        }
+
+        if options.bot_pipe.is_some() && options.spectate.is_some() {
+            return Err("Can't let a bot play while spectating".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+/// Parses the value of an address option.
+fn parse_address(arg: &str, value: Option<String>) -> Result<StreamAddress, String> {
+    value
+        .ok_or_else(|| format!("Missing address for `{}`", arg))?
+        .parse()
+}
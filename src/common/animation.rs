@@ -1,92 +1,108 @@
 //! This module contains the implementation of the struct `Animation`.
 
-use bevy::core::Timer;
+use bevy_2048::easing::Easing;
 
-/// Component used to animate with "updates".
-/// Update will occur in rate of 60 fps.
+/// A part of an animation, moving the value from `from` to `to` along a curve.
+#[derive(Debug, Copy, Clone)]
+struct Step {
+    from: f32,
+    to: f32,
+    seconds: f32,
+    easing: Easing,
+}
+
+/// Component used to animate a value over time.
+/// The value is calculated from the elapsed seconds, so animations take
+/// the same time on any frame rate. An animation is a sequence of steps,
+/// that can start after a delay.
+#[derive(Debug, Clone)]
 pub struct Animation {
-    timer: Timer,
-    ticks: usize,
-    max_ticks: usize,
-    finished: bool,
-    func: Option<fn(f32) -> f32>,
+    steps: Vec<Step>,
+    delay: f32,
+    elapsed: f32,
 }
 
 impl Animation {
-    /// Creates new animation with linear curve.
-    /// `max_ticks` is the number of updates
-    /// the animation will do.
-    pub fn new(max_ticks: usize) -> Self {
+    /// Creates a new animation from 0 to 1 that takes `seconds`.
+    pub fn new(seconds: f32, easing: Easing) -> Self {
+        Self::between(0.0, 1.0, seconds, easing)
+    }
+
+    /// Creates a new animation between two values that takes `seconds`.
+    pub fn between(from: f32, to: f32, seconds: f32, easing: Easing) -> Self {
         Self {
-            timer: Timer::from_seconds(1.0 / 60.0, true),
-            ticks: 0,
-            max_ticks,
-            finished: false,
-            func: None,
+            steps: vec![Step {
+                from,
+                to,
+                seconds,
+                easing,
+            }],
+            delay: 0.0,
+            elapsed: 0.0,
         }
     }
 
-    /// Creates new animation with a given curve.
-    /// `max_ticks` is the number of updates
-    /// the animation will do.
-    pub fn with_func(max_ticks: usize, func: fn(f32) -> f32) -> Self {
-        let mut result = Animation::new(max_ticks);
-        result.func = Some(func);
+    /// Adds a step after the last one, from the last value to `to`.
+    pub fn then(mut self, to: f32, seconds: f32, easing: Easing) -> Self {
+        let from = self.end_value();
+        self.steps.push(Step {
+            from,
+            to,
+            seconds,
+            easing,
+        });
+        self
+    }
 
-        result
+    /// Waits `seconds` before the animation starts.
+    pub fn with_delay(mut self, seconds: f32) -> Self {
+        self.delay = seconds;
+        self
     }
 
-    /// Returns a value in the range [0, 1] for the animation.
-    pub fn value(&self) -> f32 {
-        if let Some(f) = self.func {
-            f(self.base_value())
-        } else {
-            self.base_value()
-        }
+    /// Returns the number of seconds the animation takes, including its delay.
+    pub fn duration(&self) -> f32 {
+        self.delay + self.steps.iter().map(|step| step.seconds).sum::<f32>()
     }
 
-    /// Returns the reversed value, for "backwards" animation.
-    pub fn rev_value(&self) -> f32 {
-        if let Some(f) = self.func {
-            f(1.0 - self.base_value())
-        } else {
-            1.0 - self.base_value()
+    /// Returns the value of the animation at the elapsed time.
+    pub fn value(&self) -> f32 {
+        let mut time = self.elapsed - self.delay;
+        for step in self.steps.iter() {
+            if time < step.seconds {
+                let progress = (time / step.seconds).max(0.0);
+                return step.from + (step.to - step.from) * step.easing.apply(progress);
+            }
+            time -= step.seconds;
         }
+
+        self.end_value()
     }
 
-    fn base_value(&self) -> f32 {
-        self.ticks as f32 / self.max_ticks as f32
+    /// Returns the value the animation ends at.
+    fn end_value(&self) -> f32 {
+        self.steps.last().map_or(0.0, |step| step.to)
     }
 
-    /// Updates the animation, needs `delta_seconds` from the time resource.
-    /// Returns `true` if the timer finished,
-    /// which means the `value()` have been changed.
+    /// Updates the animation, needs `delta_seconds` from the game clock.
+    /// Returns `true` if the animation is running,
+    /// which means the `value()` might have been changed.
     pub fn update(&mut self, delta_seconds: f32) -> bool {
-        if !self.finished {
-            self.timer.tick(delta_seconds);
-
-            if self.timer.finished {
-                self.ticks += 1;
-                if self.ticks >= self.max_ticks {
-                    self.finished = true;
-                }
-            }
-
-            return self.timer.finished;
+        if self.finished() {
+            return false;
         }
 
-        false
+        self.elapsed = (self.elapsed + delta_seconds).min(self.duration());
+        self.elapsed > self.delay
     }
 
     /// Returns `true` if the animation finished.
     pub fn finished(&self) -> bool {
-        self.finished
+        self.elapsed >= self.duration()
     }
 
-    /// Resets the animation.
-    pub fn reset(&mut self) {
-        self.timer.reset();
-        self.ticks = 0;
-        self.finished = false;
+    /// Finishes the animation immediately.
+    pub fn skip(&mut self) {
+        self.elapsed = self.duration();
     }
 }
@@ -0,0 +1,19 @@
+//! This module contains the implementation of the enum AppState.
+
+/// This enum tells in what mode the application is running.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AppState {
+    /// The main menu is shown and the board doesn't accept any input.
+    Menu,
+    /// The local player controls the board.
+    Playing,
+    /// The board mirrors a remote game and doesn't accept any input.
+    Spectating,
+}
+
+impl Default for AppState {
+    /// Creates a Menu app state.
+    fn default() -> Self {
+        Self::Menu
+    }
+}
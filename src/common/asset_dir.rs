@@ -0,0 +1,36 @@
+//! This module contains the implementation of the asset directories,
+//! where the game finds its modes and themes.
+
+use bevy::asset::FileAssetIo;
+use std::{fs, path::PathBuf};
+
+/// Gets a directory and a file in it, returns the full path `{assets}/{dir}/{filename}`.
+pub fn asset_path(dir: &str, filename: &str) -> PathBuf {
+    let mut path = FileAssetIo::get_root_path();
+    path.push("assets");
+    path.push(dir);
+    path.push(filename);
+    path
+}
+
+/// Lists the names of the files in `{assets}/{dir}` with a given extension,
+/// without the extension and sorted.
+pub fn asset_names(dir: &str, extension: &str) -> Vec<String> {
+    let path = asset_path(dir, "");
+
+    let mut names: Vec<String> = match fs::read_dir(&path) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension() == Some(extension.as_ref()))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect(),
+        Err(e) => {
+            eprintln!("Couldn't list the files in `{}`: {}", path.display(), e);
+            Vec::new()
+        }
+    };
+
+    names.sort();
+    names
+}
@@ -0,0 +1,27 @@
+//! This module contains the implementation of the data directory,
+//! where the game saves its files.
+
+use std::{env, error::Error, fs};
+
+/// Gets `filename`, returns the full path `{path_to_exe}/data/{filename}`.
+/// If `create_dir` is true, then the directory `data` is being created.
+pub fn fulldir(filename: &str, create_dir: bool) -> Result<String, Box<dyn Error>> {
+    let mut path = env::current_exe()?
+        .parent() // removes the exe name from the path.
+        .ok_or("Couldn't get parent")?
+        .to_path_buf();
+
+    path.push("data");
+
+    if create_dir {
+        fs::create_dir_all(&path)?;
+    }
+
+    path.push(filename);
+
+    // Transforms the path into String.
+    Ok(path
+        .to_str()
+        .ok_or("Couldn't parse os_str to str")?
+        .to_owned())
+}
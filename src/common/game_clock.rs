@@ -0,0 +1,30 @@
+//! This module contains the implementation of the GameClock resource.
+use bevy::prelude::*;
+
+use super::{GameState, Settings};
+
+/// The time of the game, which stops while the game is paused.
+/// Animations use it instead of `Time`, so they freeze on pause.
+#[derive(Debug, Default)]
+pub struct GameClock {
+    pub delta_seconds: f32,
+    /// The seconds the tiles' animations advance by, scaled by the animation speed
+    /// in the settings. It's infinite when the animations are instant.
+    pub animation_seconds: f32,
+}
+
+/// This system advances the game clock, unless the game is paused.
+pub fn update_game_clock(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    mut clock: ResMut<GameClock>,
+) {
+    if game_state.is_paused() {
+        clock.delta_seconds = 0.0;
+        clock.animation_seconds = 0.0;
+    } else {
+        clock.delta_seconds = time.delta_seconds;
+        clock.animation_seconds = settings.animation_speed.scale(time.delta_seconds);
+    }
+}
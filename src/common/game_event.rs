@@ -0,0 +1,32 @@
+//! This module contains the implementation of the GameEvent event.
+
+use crate::movement::MovingDirection;
+
+use super::Position;
+
+/// Event sent whenever something meaningful happens on the board.
+/// Used by systems that want to observe the game without
+/// looking into the tiles' components.
+#[derive(Debug, Copy, Clone)]
+pub enum GameEvent {
+    /// A new tile has been spawned.
+    Spawned { position: Position, level: u32 },
+    /// The tiles have started sliding in a direction.
+    Slid { direction: MovingDirection },
+    /// No tile could slide in a direction.
+    Blocked { direction: MovingDirection },
+    /// Two tiles have been merged into a tile with the given level,
+    /// `None` if the merged tile has been removed.
+    Merged {
+        position: Position,
+        level: Option<u32>,
+    },
+    /// The score has been changed.
+    ScoreChanged { score: u32 },
+    /// The 2048 tile has been made for the first time in the game.
+    Won,
+    /// There are no more moves.
+    GameOver,
+    /// The board has been cleared for a new game.
+    NewGame,
+}
@@ -0,0 +1,23 @@
+//! This module contains the implementation of the GameRules resource.
+
+use bevy_2048::rules::{Classic, Ruleset};
+use std::ops::Deref;
+
+/// The rules of the current game mode.
+/// The board systems ask it how tiles spawn, merge and when the game is over.
+pub struct GameRules(pub Box<dyn Ruleset + Send + Sync>);
+
+impl Default for GameRules {
+    /// Creates the rules of the classic game.
+    fn default() -> Self {
+        Self(Box::new(Classic))
+    }
+}
+
+impl Deref for GameRules {
+    type Target = dyn Ruleset + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
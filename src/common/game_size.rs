@@ -1,5 +1,6 @@
 //! This module contains the implementation of GameSize.
 use bevy::prelude::*;
+use bevy_2048::rules;
 
 use crate::{
     board::{Board, EmptyTile},
@@ -20,24 +21,60 @@ impl Plugin for GameSizePlugin {
     }
 }
 
+/// The smallest and the largest number of cells in a row of the board.
+pub const MIN_CELLS: usize = 3;
+pub const MAX_CELLS: usize = 8;
+
+/// The window's size the texts are designed for in each layout, they are scaled from it.
+/// The score panels share the width of a portrait window, so its texts shrink sooner.
+const LANDSCAPE_REFERENCE: (f32, f32) = (1280.0, 720.0);
+const PORTRAIT_REFERENCE: (f32, f32) = (960.0, 1280.0);
+
+/// The board's part of the window's side that it shares with the panels.
+const BOARD_SHARE: f32 = 0.6;
+
+/// How the board and the panels are placed in the window.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Layout {
+    /// The panels are on the sides of the board.
+    Landscape,
+    /// The score panels are above the board and the help below it.
+    Portrait,
+}
+
 /// A struct that gives the size of the game's components ratioed by the board size.
 #[derive(Debug, Copy, Clone)]
-pub struct GameSize(f32);
+pub struct GameSize {
+    size: f32,
+    cells: usize,
+    layout: Layout,
+    text_scale: f32,
+}
 
 impl GameSize {
     /// Returns the board's size.
     pub fn board_size(&self) -> f32 {
-        self.0
+        self.size
+    }
+
+    /// Returns the number of cells in each row and column of the board.
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// Sets the number of cells in each row and column of the board.
+    pub fn set_cells(&mut self, cells: usize) {
+        self.cells = cells;
     }
 
     /// Calculates the tiles' size.
     pub fn tile_size(&self) -> f32 {
-        (self.0 * 0.85) / 4.0
+        (self.size * 0.85) / self.cells as f32
     }
 
     /// Calculates the space between two tiles.
     pub fn tile_spacing(&self) -> f32 {
-        (self.0 * 0.15) / 5.0
+        (self.size * 0.15) / (self.cells + 1) as f32
     }
 
     /// Calculates the amount that the tile should get increased by when a merge occur.
@@ -45,17 +82,45 @@ impl GameSize {
         self.tile_size() * 0.1
     }
 
+    /// Returns how the board and the panels are placed.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns how much the texts are scaled from their size in the reference window.
+    pub fn text_scale(&self) -> f32 {
+        self.text_scale
+    }
+
     /// Gets the window size and calculates the game size.
+    /// The layout that gives the bigger board is chosen, so a tall or narrow window
+    /// puts the panels above and below the board.
     fn calculate_game_size(&mut self, width: f32, height: f32) {
-        let (width, height) = (width * 0.9, height * 0.9);
-        self.0 = height.min(width * 0.6);
+        let landscape = (height * 0.9).min(width * 0.9 * BOARD_SHARE);
+        let portrait = (width * 0.9).min(height * 0.9 * BOARD_SHARE);
+
+        let reference = if portrait > landscape {
+            self.layout = Layout::Portrait;
+            self.size = portrait;
+            PORTRAIT_REFERENCE
+        } else {
+            self.layout = Layout::Landscape;
+            self.size = landscape;
+            LANDSCAPE_REFERENCE
+        };
+        self.text_scale = (width / reference.0).min(height / reference.1);
     }
 }
 
 impl Default for GameSize {
-    /// Creates a game size based on a board with a size of 500.
+    /// Creates a game size based on a board with a size of 500 and the classic number of cells.
     fn default() -> Self {
-        Self(500.0)
+        Self {
+            size: 500.0,
+            cells: rules::DEFAULT_SIZE,
+            layout: Layout::Landscape,
+            text_scale: 1.0,
+        }
     }
 }
 
@@ -1,11 +1,20 @@
 //! This module contains the implementation of the enum GameState.
 
+use super::Settings;
+
 // This enum tells in what state the game is in.
 #[derive(Debug)]
 pub enum GameState {
     Play,
     GameOver,
     Restarting,
+    /// The game is frozen until it's resumed.
+    Paused,
+    /// The game is frozen until the player confirms or cancels the restart,
+    /// `paused` tells if it goes back to `Paused` when it's cancelled.
+    ConfirmingRestart {
+        paused: bool,
+    },
 }
 
 impl Default for GameState {
@@ -14,3 +23,22 @@ impl Default for GameState {
         Self::Play
     }
 }
+
+impl GameState {
+    /// Restarts the game, or asks for a confirmation first if the settings want it
+    /// and the game is being played.
+    /// Restarting while the confirmation is asked confirms it.
+    pub fn restart(&mut self, settings: &Settings) {
+        *self = match *self {
+            Self::Play | Self::Paused if settings.confirm_restart => Self::ConfirmingRestart {
+                paused: matches!(*self, Self::Paused),
+            },
+            _ => Self::Restarting,
+        };
+    }
+
+    /// Checks if the game is frozen.
+    pub fn is_paused(&self) -> bool {
+        matches!(*self, Self::Paused | Self::ConfirmingRestart { .. })
+    }
+}
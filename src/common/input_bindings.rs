@@ -0,0 +1,149 @@
+//! This module contains the implementation of the InputBindings resource,
+//! which maps the actions of the player to keys.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::SettingsSection;
+
+/// An action the player can bind keys to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveLeft,
+    MoveUp,
+    MoveRight,
+    MoveDown,
+    Restart,
+    Undo,
+    Pause,
+    Hint,
+}
+
+/// All the actions, in the order they are shown.
+pub const ACTIONS: [Action; 8] = [
+    Action::MoveLeft,
+    Action::MoveUp,
+    Action::MoveRight,
+    Action::MoveDown,
+    Action::Restart,
+    Action::Undo,
+    Action::Pause,
+    Action::Hint,
+];
+
+impl Action {
+    /// Returns the key of the action's name that is shown to the player.
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "action-move-left",
+            Self::MoveUp => "action-move-up",
+            Self::MoveRight => "action-move-right",
+            Self::MoveDown => "action-move-down",
+            Self::Restart => "action-restart",
+            Self::Undo => "action-undo",
+            Self::Pause => "action-pause",
+            Self::Hint => "action-hint",
+        }
+    }
+
+    /// Returns the keys the action is bound to by default.
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            Self::MoveLeft => vec![KeyCode::Left, KeyCode::A],
+            Self::MoveUp => vec![KeyCode::Up, KeyCode::W],
+            Self::MoveRight => vec![KeyCode::Right, KeyCode::D],
+            Self::MoveDown => vec![KeyCode::Down, KeyCode::S],
+            Self::Restart => vec![KeyCode::Space],
+            Self::Undo => vec![KeyCode::Z],
+            Self::Pause => vec![KeyCode::Escape],
+            Self::Hint => vec![KeyCode::H],
+        }
+    }
+}
+
+/// This struct maps every action to the keys that trigger it,
+/// it's saved as the `bindings` section of the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputBindings(BTreeMap<Action, Vec<KeyCode>>);
+
+impl SettingsSection for InputBindings {
+    const NAME: &'static str = "bindings";
+
+    fn defaults() -> Self {
+        Self::defaults()
+    }
+
+    /// Actions that were added after the bindings were saved get their default keys.
+    fn complete(&mut self) {
+        for &action in ACTIONS.iter() {
+            self.0
+                .entry(action)
+                .or_insert_with(|| action.default_keys());
+        }
+    }
+}
+
+impl InputBindings {
+    /// Creates the default bindings.
+    pub fn defaults() -> Self {
+        Self(
+            ACTIONS
+                .iter()
+                .map(|&action| (action, action.default_keys()))
+                .collect(),
+        )
+    }
+
+    /// Returns the keys bound to an action.
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the names of the keys bound to an action joined with `separator`,
+    /// or `-` when the action has no key.
+    pub fn key_names(&self, action: Action, separator: &str) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+
+        keys.iter()
+            .map(|key| format!("{:?}", key))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Returns the actions a key is bound to.
+    pub fn actions(&self, key: KeyCode) -> impl Iterator<Item = Action> + '_ {
+        self.0
+            .iter()
+            .filter(move |(_, keys)| keys.contains(&key))
+            .map(|(&action, _)| action)
+    }
+
+    /// Checks if any key bound to an action was just pressed.
+    pub fn just_pressed(&self, action: Action, keyboard: &Input<KeyCode>) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|&key| keyboard.just_pressed(key))
+    }
+
+    /// Binds a key to an action, or unbinds it if it's already bound to the action.
+    /// Returns the action the key is bound to if it's bound to another action.
+    pub fn toggle(&mut self, action: Action, key: KeyCode) -> Result<(), Action> {
+        if let Some(conflict) = self.actions(key).find(|&other| other != action) {
+            return Err(conflict);
+        }
+
+        let keys = self.0.entry(action).or_default();
+        if let Some(index) = keys.iter().position(|&bound| bound == key) {
+            keys.remove(index);
+        } else {
+            keys.push(key);
+        }
+
+        Ok(())
+    }
+}
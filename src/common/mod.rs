@@ -4,11 +4,38 @@ mod animation;
 pub use animation::Animation;
 
 mod tile_components;
-pub use tile_components::{Position, Tile};
+pub use tile_components::{rules_board, Position, Tile};
+
+mod app_state;
+pub use app_state::AppState;
 
 mod game_state;
 pub use game_state::GameState;
 
+mod game_clock;
+pub use game_clock::{update_game_clock, GameClock};
+
+mod game_event;
+pub use game_event::GameEvent;
+
+mod game_rules;
+pub use game_rules::GameRules;
+
+mod data_dir;
+pub use data_dir::fulldir;
+
+mod asset_dir;
+pub use asset_dir::{asset_names, asset_path};
+
+mod settings;
+pub use settings::{
+    step_volume, AddSettingsSection, Settings, SettingsChanged, SettingsPlugin, SettingsSection,
+    ANIMATION_SPEEDS, NUMBER_STYLES,
+};
+
+mod input_bindings;
+pub use input_bindings::{Action, InputBindings, ACTIONS};
+
 mod game_size;
 pub use game_size::GameSizePlugin;
-pub use game_size::GameSize;
\ No newline at end of file
+pub use game_size::{GameSize, Layout, MAX_CELLS, MIN_CELLS};
@@ -0,0 +1,558 @@
+//! This module contains the implementation of the Settings resource,
+//! which saves the player's preferences, and of the sections other features
+//! save with the settings.
+//! The settings are saved whenever they change, and a `SettingsChanged` event
+//! is sent for every setting that has changed, so the systems can react to it.
+
+use bevy::prelude::*;
+use bevy_2048::rules;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{collections::BTreeMap, error::Error, fs, io::ErrorKind};
+
+use super::{fulldir, InputBindings};
+use crate::locale::{system_language, LocalizedText};
+
+/// The file in the data directory the settings are saved into.
+const SETTINGS_FILE: &str = "settings.json";
+
+/// The version of the settings' schema, it goes up when settings are moved or renamed.
+/// The files of older versions are migrated when they are loaded.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Reads a file of an older version from the data directory, `None` if it's missing.
+type ReadLegacy<'a> = &'a dyn Fn(&str) -> Option<Value>;
+
+/// A migration of the schema, it moves the settings of a file one version up.
+type Migration = fn(&mut Map<String, Value>, ReadLegacy);
+
+/// The migrations of the schema, the migration at index `i` moves the settings
+/// from version `i` to version `i + 1`.
+const MIGRATIONS: [Migration; SETTINGS_VERSION as usize] = [bindings_into_sections];
+
+/// The file the key bindings were saved into before version 1.
+const LEGACY_BINDINGS_FILE: &str = "bindings.json";
+
+/// This plugin saves the settings and sends their change events.
+/// The `Settings` resource is added before it, since the window is created with them.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.add_event::<SettingsChanged>()
+            .init_resource::<SavedSettings>()
+            // The settings are changed during the update stage.
+            .add_system_to_stage(stage::POST_UPDATE, save_settings.system());
+    }
+}
+
+/// Event sent when a setting has changed.
+#[derive(Debug, Clone)]
+pub struct SettingsChanged {
+    /// The setting's name in the file, like `theme`, or `sections.<name>` for a section.
+    pub key: String,
+}
+
+/// This struct saves the player's preferences into `settings.json` in the data directory.
+/// Settings that are missing from the file keep their default values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default = "Settings::defaults")]
+pub struct Settings {
+    /// The version of the schema the settings were saved with.
+    pub version: u32,
+    /// The name of the theme in `assets/themes`.
+    pub theme: String,
+    /// The name of the skin in `assets/skins`, `None` draws the tiles with the theme's colors.
+    pub skin: Option<String>,
+    /// How the values of the tiles are written.
+    pub tile_numbers: NumberStyle,
+    /// Whether a pattern is drawn on the tiles, so their levels can be told apart without colors.
+    pub tile_patterns: bool,
+    /// How fast the tiles are animated.
+    pub animation_speed: AnimationSpeed,
+    /// Whether particles fly out of merged tiles, they can be disabled to reduce motion.
+    pub particles: bool,
+    /// The volume of all the audio, from 0 to 1.
+    pub master_volume: f32,
+    /// The volume of the sound effects, from 0 to 1.
+    pub sfx_volume: f32,
+    /// The volume of the music, from 0 to 1. The music doesn't play at 0.
+    pub music_volume: f32,
+    /// Whether all the audio is muted.
+    pub muted: bool,
+    /// The language of the texts, the name of a locale in `assets/locales`.
+    pub language: String,
+    /// The size of the window and whether it's fullscreen.
+    pub window: WindowSettings,
+    /// Whether restarting a game that is being played asks for a confirmation.
+    pub confirm_restart: bool,
+    /// The sections of the other features by their names, see `SettingsSection`.
+    pub sections: BTreeMap<String, Value>,
+}
+
+/// The size of the window when it isn't fullscreen, and whether it's fullscreen.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            fullscreen: false,
+        }
+    }
+}
+
+/// The ways the value of a tile can be written.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NumberStyle {
+    /// The whole value, like 16384.
+    Plain,
+    /// Values from 10000 are written in thousands of 1024, like 16K or 1M.
+    Abbreviated,
+    /// The value as a power of 2, like 2^14.
+    Exponent,
+}
+
+/// All the number styles in the order they are cycled through.
+pub const NUMBER_STYLES: [NumberStyle; 3] = [
+    NumberStyle::Plain,
+    NumberStyle::Abbreviated,
+    NumberStyle::Exponent,
+];
+
+impl NumberStyle {
+    /// Returns the key of the name that is shown to the player.
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Plain => "number-style-plain",
+            Self::Abbreviated => "number-style-abbreviated",
+            Self::Exponent => "number-style-exponent",
+        }
+    }
+
+    /// Writes the value of a tile with the given level.
+    pub fn format(self, level: u32) -> String {
+        let value = rules::tile_score(level);
+        match self {
+            Self::Abbreviated if value >= 1 << 20 => format!("{}M", value >> 20),
+            Self::Abbreviated if value >= 10_000 => format!("{}K", value >> 10),
+            Self::Plain | Self::Abbreviated => value.to_string(),
+            Self::Exponent => format!("2^{}", level + 1),
+        }
+    }
+}
+
+/// How fast the tiles are animated.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnimationSpeed {
+    /// The animations' lengths are multiplied by the inverse of the value,
+    /// which is kept between 0.5 and 3.
+    Multiplier(f32),
+    /// The tiles skip their animations, so a move is done in a single frame.
+    Instant,
+}
+
+/// All the animation speeds in the order they are cycled through.
+pub const ANIMATION_SPEEDS: [AnimationSpeed; 7] = [
+    AnimationSpeed::Multiplier(0.5),
+    AnimationSpeed::Multiplier(0.75),
+    AnimationSpeed::Multiplier(1.0),
+    AnimationSpeed::Multiplier(1.5),
+    AnimationSpeed::Multiplier(2.0),
+    AnimationSpeed::Multiplier(3.0),
+    AnimationSpeed::Instant,
+];
+
+impl AnimationSpeed {
+    /// Returns the name that is shown to the player.
+    pub fn name(self) -> LocalizedText {
+        match self {
+            Self::Multiplier(multiplier) => LocalizedText::new("animation-multiplier")
+                .with_decimal("value", multiplier.clamp(0.5, 3.0)),
+            Self::Instant => LocalizedText::new("animation-instant"),
+        }
+    }
+
+    /// Scales the seconds that passed into the seconds the animations advance by.
+    /// Instant animations advance by an infinite time, so they finish right away.
+    pub fn scale(self, seconds: f32) -> f32 {
+        match self {
+            Self::Multiplier(multiplier) => seconds * multiplier.clamp(0.5, 3.0),
+            Self::Instant => f32::INFINITY,
+        }
+    }
+}
+
+/// How much a volume changes with each step of its slider.
+pub const VOLUME_STEP: f32 = 0.1;
+
+/// Moves a volume a step up or down, keeping it between 0 and 1.
+/// The volume is rounded to the steps, so it can't drift away from them.
+pub fn step_volume(volume: f32, up: bool) -> f32 {
+    let steps = (volume / VOLUME_STEP).round() + if up { 1.0 } else { -1.0 };
+    (steps * VOLUME_STEP).clamp(0.0, 1.0)
+}
+
+impl Default for Settings {
+    /// Trys to load the settings from the file.
+    /// If it fails it uses the default settings.
+    fn default() -> Self {
+        match Self::load() {
+            Ok(settings) => settings,
+            Err(e) => {
+                eprintln!("Couldn't load the settings: {}", e);
+                Self::defaults()
+            }
+        }
+    }
+}
+
+impl Settings {
+    /// Creates the default settings.
+    pub fn defaults() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            theme: "rainbow".to_string(),
+            skin: None,
+            tile_numbers: NumberStyle::Plain,
+            tile_patterns: false,
+            animation_speed: AnimationSpeed::Multiplier(1.0),
+            particles: true,
+            master_volume: 1.0,
+            sfx_volume: 0.8,
+            music_volume: 0.5,
+            muted: false,
+            language: system_language(),
+            window: WindowSettings::default(),
+            confirm_restart: false,
+            sections: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the settings from the file, and migrates them if they are from an older version.
+    /// Without a file, the files of older versions are still migrated.
+    fn load() -> Result<Self, Box<dyn Error>> {
+        let file: Map<String, Value> = match fs::read_to_string(fulldir(SETTINGS_FILE, false)?) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => Map::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let read_legacy = |filename: &str| {
+            let contents = fs::read_to_string(fulldir(filename, false).ok()?).ok()?;
+            serde_json::from_str(&contents).ok()
+        };
+        let (settings, migrated) = Self::migrate(file, &read_legacy)?;
+        if migrated {
+            settings.save();
+        }
+
+        Ok(settings)
+    }
+
+    /// Migrates the settings of a file to the current version, and keeps the volumes in their range.
+    /// Returns the settings and whether they were migrated.
+    fn migrate(
+        mut file: Map<String, Value>,
+        read_legacy: ReadLegacy,
+    ) -> Result<(Self, bool), serde_json::Error> {
+        let version = file.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+        if version > SETTINGS_VERSION as usize {
+            eprintln!(
+                "The settings are from a newer version {}, the unknown settings are dropped",
+                version
+            );
+        }
+        for migrate in MIGRATIONS.iter().skip(version) {
+            migrate(&mut file, read_legacy);
+        }
+
+        let mut settings: Self = serde_json::from_value(Value::Object(file))?;
+        settings.version = SETTINGS_VERSION;
+        // A hand-edited file can have volumes outside of their range.
+        for volume in [
+            &mut settings.master_volume,
+            &mut settings.sfx_volume,
+            &mut settings.music_volume,
+        ]
+        .iter_mut()
+        {
+            **volume = volume.clamp(0.0, 1.0);
+        }
+
+        Ok((settings, version < SETTINGS_VERSION as usize))
+    }
+
+    /// Returns a section, or its defaults if it's missing or can't be read.
+    pub fn section<T: SettingsSection>(&self) -> T {
+        let mut section = match self.sections.get(T::NAME) {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+                eprintln!("Couldn't load the settings section `{}`: {}", T::NAME, e);
+                T::defaults()
+            }),
+            None => T::defaults(),
+        };
+
+        section.complete();
+        section
+    }
+
+    /// Returns the volume of the sound effects, after the master volume and the mute.
+    pub fn effective_sfx_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.sfx_volume
+        }
+    }
+
+    /// Returns the volume of the music, after the master volume and the mute.
+    pub fn effective_music_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.music_volume
+        }
+    }
+
+    /// Saves the settings into the file.
+    /// Errors are printed into the console.
+    pub fn save(&self) {
+        let result = fulldir(SETTINGS_FILE, true).and_then(|filepath| {
+            fs::write(filepath, serde_json::to_string_pretty(self)?)?;
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            eprintln!("Couldn't save the settings: {}", e);
+        }
+    }
+}
+
+/// Version 1 saves the key bindings as a section of the settings,
+/// they were saved into their own file before.
+fn bindings_into_sections(file: &mut Map<String, Value>, read_legacy: ReadLegacy) {
+    if let Some(bindings) = read_legacy(LEGACY_BINDINGS_FILE) {
+        let sections = file
+            .entry("sections")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Some(sections) = sections.as_object_mut() {
+            sections.entry(InputBindings::NAME).or_insert(bindings);
+        }
+    }
+}
+
+/// A part of the settings that another feature defines, it's saved under its name
+/// in the `sections` of the settings file.
+/// A section is added to the app as a resource with `add_settings_section`.
+pub trait SettingsSection:
+    Serialize + DeserializeOwned + Clone + PartialEq + Send + Sync + 'static
+{
+    /// The section's name in the settings file.
+    const NAME: &'static str;
+
+    /// Creates the default section, used when the settings don't have it.
+    fn defaults() -> Self;
+
+    /// Completes a loaded section, like adding what an older version of it didn't save.
+    fn complete(&mut self) {}
+}
+
+/// Adds the settings sections to the app.
+pub trait AddSettingsSection {
+    /// Loads a section from the settings as a resource, and saves it with the settings
+    /// when the resource changes.
+    fn add_settings_section<T: SettingsSection>(&mut self) -> &mut Self;
+}
+
+impl AddSettingsSection for AppBuilder {
+    fn add_settings_section<T: SettingsSection>(&mut self) -> &mut Self {
+        let section = self
+            .resources()
+            .get::<Settings>()
+            .expect("The settings are added before their sections")
+            .section::<T>();
+
+        self.add_resource(section)
+            .add_system(store_section::<T>.system())
+    }
+}
+
+/// This system writes a section into the settings when it changes.
+fn store_section<T: SettingsSection>(section: ChangedRes<T>, mut settings: ResMut<Settings>) {
+    let value = match serde_json::to_value(&*section) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("Couldn't save the settings section `{}`: {}", T::NAME, e);
+            return;
+        }
+    };
+
+    if settings.sections.get(T::NAME) != Some(&value) {
+        settings.sections.insert(T::NAME.to_string(), value);
+    }
+}
+
+/// The settings as they were last saved, to tell which settings have changed.
+pub struct SavedSettings(Settings);
+
+impl FromResources for SavedSettings {
+    fn from_resources(resources: &Resources) -> Self {
+        let settings = resources.get::<Settings>().unwrap();
+        Self((*settings).clone())
+    }
+}
+
+/// Returns the names of the settings that differ, the sections are compared one by one.
+fn changed_keys(old: &Settings, new: &Settings) -> Vec<String> {
+    let (old, new) = match (serde_json::to_value(old), serde_json::to_value(new)) {
+        (Ok(Value::Object(old)), Ok(Value::Object(new))) => (old, new),
+        _ => return Vec::new(),
+    };
+
+    let mut keys = Vec::new();
+    for (key, value) in new.iter() {
+        if key == "sections" {
+            let empty = Map::new();
+            let old_sections = old.get(key).and_then(Value::as_object).unwrap_or(&empty);
+            let new_sections = value.as_object().unwrap_or(&empty);
+            for name in old_sections.keys().chain(new_sections.keys()) {
+                let key = format!("sections.{}", name);
+                if old_sections.get(name) != new_sections.get(name) && !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        } else if old.get(key) != Some(value) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+/// This system saves the settings when they change, and sends an event
+/// for every setting that has changed.
+fn save_settings(
+    settings: ChangedRes<Settings>,
+    mut saved: ResMut<SavedSettings>,
+    mut events: ResMut<Events<SettingsChanged>>,
+) {
+    if *settings == saved.0 {
+        return;
+    }
+
+    for key in changed_keys(&saved.0, &settings) {
+        events.send(SettingsChanged { key });
+    }
+
+    settings.save();
+    saved.0 = settings.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Action;
+    use serde_json::json;
+
+    fn no_legacy(_: &str) -> Option<Value> {
+        None
+    }
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("Expected an object"),
+        }
+    }
+
+    #[test]
+    fn missing_settings_get_their_defaults() {
+        let file = object(json!({ "version": SETTINGS_VERSION, "theme": "dark" }));
+        let (settings, migrated) = Settings::migrate(file, &no_legacy).unwrap();
+
+        assert!(!migrated);
+        assert_eq!(settings.theme, "dark");
+        assert_eq!(settings.window, WindowSettings::default());
+        assert_eq!(
+            settings.confirm_restart,
+            Settings::defaults().confirm_restart
+        );
+    }
+
+    #[test]
+    fn unversioned_file_is_migrated() {
+        let file = object(json!({ "theme": "dark" }));
+        let (settings, migrated) = Settings::migrate(file, &no_legacy).unwrap();
+
+        assert!(migrated);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.theme, "dark");
+    }
+
+    #[test]
+    fn volumes_are_kept_in_their_range() {
+        let file = object(json!({
+            "version": SETTINGS_VERSION,
+            "master_volume": 5.0,
+            "sfx_volume": -1.0,
+            "music_volume": 0.3,
+        }));
+        let (settings, _) = Settings::migrate(file, &no_legacy).unwrap();
+
+        assert_eq!(settings.master_volume, 1.0);
+        assert_eq!(settings.sfx_volume, 0.0);
+        assert_eq!(settings.music_volume, 0.3);
+    }
+
+    #[test]
+    fn legacy_bindings_move_into_their_section() {
+        let read_legacy = |filename: &str| {
+            assert_eq!(filename, LEGACY_BINDINGS_FILE);
+            Some(json!({ "undo": ["U"] }))
+        };
+        let (settings, migrated) = Settings::migrate(Map::new(), &read_legacy).unwrap();
+        assert!(migrated);
+
+        let bindings = settings.section::<InputBindings>();
+        assert_eq!(bindings.keys(Action::Undo), &[KeyCode::U]);
+        // Actions the legacy file didn't have keep their default keys.
+        assert_eq!(
+            bindings.keys(Action::Pause),
+            InputBindings::defaults().keys(Action::Pause)
+        );
+    }
+
+    #[test]
+    fn current_sections_are_not_replaced_by_legacy_files() {
+        let file = object(json!({ "sections": { "bindings": { "undo": ["Y"] } } }));
+        let read_legacy = |_: &str| Some(json!({ "undo": ["U"] }));
+        let (settings, _) = Settings::migrate(file, &read_legacy).unwrap();
+
+        assert_eq!(
+            settings.section::<InputBindings>().keys(Action::Undo),
+            &[KeyCode::Y]
+        );
+    }
+
+    #[test]
+    fn changed_keys_name_the_settings_and_sections() {
+        let old = Settings::defaults();
+        let mut new = old.clone();
+        new.theme = "dark".to_string();
+        new.window.fullscreen = !new.window.fullscreen;
+        new.sections.insert("gestures".to_string(), json!({}));
+
+        assert_eq!(
+            changed_keys(&old, &new),
+            vec!["sections.gestures", "theme", "window"]
+        );
+        assert!(changed_keys(&old, &old).is_empty());
+    }
+}
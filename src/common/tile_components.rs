@@ -1,6 +1,7 @@
 //! This module contains the implementation of the components Tile and Position.
 
 use bevy::prelude::*;
+use bevy_2048::rules::{self, Board};
 
 use super::GameSize;
 
@@ -11,28 +12,23 @@ pub struct Tile {
 }
 
 impl Tile {
-    /// Each level has a unique color (up to 9).
-    /// Returns the color for a given tile.
-    pub fn color(&self) -> Color {
-        match self.level {
-            0 => Color::rgb_u8(255, 255, 0),  // Yellow
-            1 => Color::rgb_u8(255, 69, 0),   // Orange Red
-            2 => Color::rgb_u8(255, 0, 0),    // Red
-            3 => Color::rgb_u8(255, 0, 255),  // Magenta
-            4 => Color::rgb_u8(75, 0, 130),   // Indigo
-            5 => Color::rgb_u8(0, 0, 255),    // Blue
-            6 => Color::rgb_u8(0, 255, 255),  // Cyan
-            7 => Color::rgb_u8(0, 255, 0),    // Green
-            8 => Color::rgb_u8(139, 69, 19),  // Saddle Brown
-            9 => Color::rgb_u8(184, 134, 11), // Dark Golden Rod
-            _ => Color::BLACK,
-        }
-    }
-
     /// Calculates the score of a given tile (pow(2, level)).
     pub fn score(&self) -> u32 {
-        2u32.pow(self.level + 1)
+        rules::tile_score(self.level)
+    }
+}
+
+/// Creates a board of the rules with `cells` rows and columns from the tiles' components.
+pub fn rules_board<'a>(
+    cells: usize,
+    tiles: impl Iterator<Item = (&'a Tile, &'a Position)>,
+) -> Board {
+    let mut board = Board::new(cells);
+    for (tile, position) in tiles {
+        board.set(position.row, position.col, Some(tile.level));
     }
+
+    board
 }
 /// Component for saving the position of a tile in the grid.
 #[derive(Debug, Default, PartialEq, Copy, Clone)]
@@ -42,10 +38,10 @@ pub struct Position {
 }
 
 impl Position {
-    /// Calculates the index of the position on a board
-    /// represented by a 1D array.
-    pub fn index(&self) -> usize {
-        self.row * 4 + self.col
+    /// Calculates the index of the position on a board with `cells`
+    /// rows and columns represented by a 1D array.
+    pub fn index(&self, cells: usize) -> usize {
+        self.row * cells + self.col
     }
 
     /// Transforms a position into a world point according to the board's size.
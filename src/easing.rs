@@ -0,0 +1,73 @@
+//! This module contains the easing curves that animations progress along.
+//! Every curve takes the animation's linear progress in the range [0, 1]
+//! and starts at 0 and ends at 1, though some overshoot in between.
+
+use std::f32::consts::PI;
+
+/// A named easing curve.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Easing {
+    Linear,
+    /// Starts slow and speeds up.
+    InCubic,
+    /// Starts fast and slows down.
+    OutCubic,
+    /// Starts slow, speeds up and slows down at the end.
+    InOutCubic,
+    /// Overshoots the end and comes back to it.
+    OutBack,
+    /// Springs around the end before settling on it.
+    OutElastic,
+    /// Bounces on the end like a dropped ball.
+    OutBounce,
+}
+
+impl Easing {
+    /// Returns the eased value of a progress in the range [0, 1].
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::InCubic => t * t * t,
+            Self::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::InOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Self::OutBack => {
+                const OVERSHOOT: f32 = 1.701_58;
+                1.0 + (OVERSHOOT + 1.0) * (t - 1.0).powi(3) + OVERSHOOT * (t - 1.0).powi(2)
+            }
+            Self::OutElastic => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }
+            Self::OutBounce => out_bounce(t),
+        }
+    }
+}
+
+/// The bounce curve is made of parabolas that get lower, like the bounces of a ball.
+fn out_bounce(t: f32) -> f32 {
+    const STRENGTH: f32 = 7.5625;
+    const WIDTH: f32 = 2.75;
+
+    if t < 1.0 / WIDTH {
+        STRENGTH * t * t
+    } else if t < 2.0 / WIDTH {
+        let t = t - 1.5 / WIDTH;
+        STRENGTH * t * t + 0.75
+    } else if t < 2.5 / WIDTH {
+        let t = t - 2.25 / WIDTH;
+        STRENGTH * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / WIDTH;
+        STRENGTH * t * t + 0.984_375
+    }
+}
@@ -0,0 +1,346 @@
+//! This module contains a gym-style environment of the game,
+//! for training agents without running the game itself.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::rules::{Board, Direction, DEFAULT_SIZE, DIRECTIONS};
+
+/// The weights of the reward's parts.
+/// The reward of a step is the weighted sum of the parts.
+#[derive(Debug, Copy, Clone)]
+pub struct RewardConfig {
+    /// Weight of the score gained by the step.
+    pub score_delta: f32,
+    /// Weight of the number of empty cells after the step.
+    pub empty_cells: f32,
+    /// Weight of the increase of the highest level on the board.
+    pub max_tile: f32,
+    /// Reward of an action that doesn't move any tile.
+    pub invalid_action: f32,
+}
+
+impl Default for RewardConfig {
+    /// Rewards the score gained by the step only.
+    fn default() -> Self {
+        Self {
+            score_delta: 1.0,
+            empty_cells: 0.0,
+            max_tile: 0.0,
+            invalid_action: 0.0,
+        }
+    }
+}
+
+/// The configuration of an environment.
+#[derive(Debug, Copy, Clone)]
+pub struct EnvConfig {
+    pub size: usize,
+    /// Number of tiles to spawn on reset.
+    pub starting_tiles: usize,
+    pub reward: RewardConfig,
+}
+
+impl Default for EnvConfig {
+    /// Creates the configuration of the classic game.
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_SIZE,
+            starting_tiles: 2,
+            reward: RewardConfig::default(),
+        }
+    }
+}
+
+/// What the agent sees after each step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// The board row after row, from the bottom row.
+    /// 0 is an empty cell and `n` is a tile with level `n - 1`.
+    pub board: Vec<u32>,
+    /// Tells for each action in `DIRECTIONS` if it moves any tile.
+    pub legal_actions: [bool; 4],
+}
+
+/// Extra information about a step.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Info {
+    /// The score of the current episode.
+    pub score: u32,
+    pub max_level: Option<u32>,
+    /// Tells if the action moved any tile.
+    pub moved: bool,
+}
+
+/// A single game of 2048.
+#[derive(Debug, Clone)]
+pub struct Env {
+    config: EnvConfig,
+    board: Board,
+    score: u32,
+    rng: StdRng,
+}
+
+impl Env {
+    /// Creates an environment, `reset` should be called before stepping.
+    pub fn new(config: EnvConfig) -> Self {
+        Self {
+            config,
+            board: Board::new(config.size),
+            score: 0,
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    /// Starts a new episode. The same seed always creates the same episode.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.board = Board::new(self.config.size);
+        self.score = 0;
+
+        for _ in 0..self.config.starting_tiles {
+            self.board.spawn_random(&mut self.rng);
+        }
+
+        self.observation()
+    }
+
+    /// Moves the tiles in the direction of the action
+    /// and spawns a new tile if any tile has been moved.
+    /// Returns the observation, the reward, whether the episode is done and extra information.
+    pub fn step(&mut self, action: Direction) -> (Observation, f32, bool, Info) {
+        let reward_config = self.config.reward;
+        let result = self.board.slide(action);
+
+        let reward = if result.moved {
+            let old_max = self.board.max_level();
+
+            self.board = result.board;
+            self.score = self.score.saturating_add(result.score);
+            self.board.spawn_random(&mut self.rng);
+
+            let max_increase = match (old_max, self.board.max_level()) {
+                (Some(old), Some(new)) if new > old => (new - old) as f32,
+                _ => 0.0,
+            };
+
+            reward_config.score_delta * result.score as f32
+                + reward_config.empty_cells * self.board.empty_cells().len() as f32
+                + reward_config.max_tile * max_increase
+        } else {
+            reward_config.invalid_action
+        };
+
+        let info = Info {
+            score: self.score,
+            max_level: self.board.max_level(),
+            moved: result.moved,
+        };
+
+        (self.observation(), reward, self.done(), info)
+    }
+
+    /// Returns the current observation.
+    pub fn observation(&self) -> Observation {
+        Observation {
+            board: self
+                .board
+                .cells()
+                .iter()
+                .map(|level| level.map_or(0, |level| level + 1))
+                .collect(),
+            legal_actions: self.legal_actions(),
+        }
+    }
+
+    /// Returns for each action in `DIRECTIONS` if it moves any tile.
+    pub fn legal_actions(&self) -> [bool; 4] {
+        let mut mask = [false; 4];
+        for direction in DIRECTIONS.iter() {
+            mask[direction.index()] = self.board.can_move(*direction);
+        }
+
+        mask
+    }
+
+    /// Tells if there are no moves left.
+    pub fn done(&self) -> bool {
+        self.board.is_game_over()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+}
+
+/// A batch of environments stepped together.
+/// Environments that are done are reset automatically,
+/// so the returned observation is the first one of the new episode.
+#[derive(Debug, Clone)]
+pub struct VecEnv {
+    envs: Vec<Env>,
+    rng: StdRng,
+}
+
+impl VecEnv {
+    /// Creates `count` environments with the same configuration.
+    pub fn new(count: usize, config: EnvConfig) -> Self {
+        Self {
+            envs: vec![Env::new(config); count],
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    /// Resets all the environments. Each environment gets
+    /// its own seed derived from the given seed.
+    pub fn reset(&mut self, seed: u64) -> Vec<Observation> {
+        self.rng = StdRng::seed_from_u64(seed);
+
+        let rng = &mut self.rng;
+        self.envs
+            .iter_mut()
+            .map(|env| env.reset(rng.gen()))
+            .collect()
+    }
+
+    /// Steps every environment with its action.
+    /// Panics if the number of actions isn't the number of environments.
+    pub fn step(&mut self, actions: &[Direction]) -> Vec<(Observation, f32, bool, Info)> {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "VecEnv::step(): Expected an action for each environment."
+        );
+
+        let rng = &mut self.rng;
+        self.envs
+            .iter_mut()
+            .zip(actions)
+            .map(|(env, action)| {
+                let (mut observation, reward, done, info) = env.step(*action);
+                if done {
+                    observation = env.reset(rng.gen());
+                }
+
+                (observation, reward, done, info)
+            })
+            .collect()
+    }
+
+    /// Returns the legal actions mask of each environment.
+    pub fn legal_actions(&self) -> Vec<[bool; 4]> {
+        self.envs.iter().map(Env::legal_actions).collect()
+    }
+
+    pub fn envs(&self) -> &[Env] {
+        &self.envs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the first legal action of an observation.
+    fn first_legal(observation: &Observation) -> Direction {
+        DIRECTIONS
+            .iter()
+            .copied()
+            .find(|direction| observation.legal_actions[direction.index()])
+            .unwrap_or(Direction::Left)
+    }
+
+    #[test]
+    fn reset_is_deterministic() {
+        let mut first = Env::new(EnvConfig::default());
+        let mut second = Env::new(EnvConfig::default());
+
+        let mut observation = first.reset(42);
+        assert_eq!(observation, second.reset(42));
+        assert_eq!(
+            observation.board.iter().filter(|&&cell| cell > 0).count(),
+            2
+        );
+
+        for _ in 0..50 {
+            let action = first_legal(&observation);
+            let (next, reward, done, _) = first.step(action);
+            let (other, other_reward, other_done, _) = second.step(action);
+            assert_eq!(next, other);
+            assert_eq!(reward, other_reward);
+            assert_eq!(done, other_done);
+            if done {
+                break;
+            }
+            observation = next;
+        }
+    }
+
+    #[test]
+    fn illegal_action_is_rewarded_as_invalid() {
+        let config = EnvConfig {
+            reward: RewardConfig {
+                invalid_action: -1.0,
+                ..RewardConfig::default()
+            },
+            ..EnvConfig::default()
+        };
+        let mut env = Env::new(config);
+        env.reset(7);
+
+        // A single tile in the top left corner can't move left.
+        env.board = Board::new(config.size);
+        env.board.set(0, 0, Some(0));
+        let observation = env.observation();
+        assert!(!observation.legal_actions[Direction::Left.index()]);
+
+        let (next, reward, _, info) = env.step(Direction::Left);
+        assert_eq!(next, observation);
+        assert_eq!(reward, -1.0);
+        assert!(!info.moved);
+    }
+
+    #[test]
+    fn vec_env_resets_done_envs() {
+        // A small board ends quickly.
+        let config = EnvConfig {
+            size: 2,
+            ..EnvConfig::default()
+        };
+        let mut envs = VecEnv::new(2, config);
+        let mut observations = envs.reset(3);
+
+        let mut resets = 0;
+        for _ in 0..1000 {
+            let actions: Vec<Direction> = observations.iter().map(first_legal).collect();
+            let results = envs.step(&actions);
+
+            for (i, (observation, _, done, _)) in results.iter().enumerate() {
+                if *done {
+                    resets += 1;
+                    // The observation is the first one of the new episode.
+                    assert_eq!(envs.envs()[i].score(), 0);
+                    assert_eq!(
+                        observation.board.iter().filter(|&&cell| cell > 0).count(),
+                        config.starting_tiles
+                    );
+                    assert!(!envs.envs()[i].done());
+                }
+            }
+            observations = results.into_iter().map(|result| result.0).collect();
+        }
+
+        assert!(resets > 0);
+    }
+}
@@ -0,0 +1,213 @@
+//! This module contains the implementation of the gamepad input.
+//! The D-pad and the left stick move the tiles and Start restarts the game.
+//! The face buttons are left unassigned, there is no undo or hint to trigger yet.
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{AddSettingsSection, AppState, GameState, Settings, SettingsSection},
+    locale::{Locale, LocalizedText},
+    movement::{MovingDirection, MovingInputEvent},
+    ui::ToastEvent,
+};
+
+/// The shortest time a held direction waits before it's repeated, in seconds.
+const MIN_REPEAT_SECONDS: f32 = 0.05;
+
+/// The settings of the gamepad input, saved as the `gamepad` section of the settings.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadInputSettings {
+    /// How far the left stick should be pushed to choose a direction.
+    pub dead_zone: f32,
+    /// How long a direction should be held before it's repeated, in seconds.
+    pub repeat_delay: f32,
+    /// The time between two repeats of a held direction, in seconds.
+    pub repeat_interval: f32,
+}
+
+impl Default for GamepadInputSettings {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.5,
+            repeat_delay: 0.4,
+            repeat_interval: 0.2,
+        }
+    }
+}
+
+impl SettingsSection for GamepadInputSettings {
+    const NAME: &'static str = "gamepad";
+
+    fn defaults() -> Self {
+        Self::default()
+    }
+
+    /// Values that were edited in the file are kept usable: the stick can still
+    /// leave the dead zone, and a held direction isn't repeated every frame.
+    fn complete(&mut self) {
+        self.dead_zone = self.dead_zone.clamp(0.1, 0.9);
+        self.repeat_delay = self.repeat_delay.max(MIN_REPEAT_SECONDS);
+        self.repeat_interval = self.repeat_interval.max(MIN_REPEAT_SECONDS);
+    }
+}
+
+/// This plugin builds the gamepad systems into the app.
+pub struct GamepadInputPlugin;
+
+impl Plugin for GamepadInputPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.add_settings_section::<GamepadInputSettings>()
+            .init_resource::<GamepadListener>()
+            .init_resource::<ConnectedGamepads>()
+            .add_system(gamepad_connections.system())
+            .add_system(gamepad_moving_input.system())
+            .add_system(gamepad_new_game.system());
+    }
+}
+
+/// Event listener for GamepadEvent.
+#[derive(Default)]
+pub struct GamepadListener {
+    pub reader: EventReader<GamepadEvent>,
+}
+
+/// The direction a gamepad is holding, and the time left until it's repeated.
+#[derive(Debug, Default)]
+pub struct HeldDirection {
+    direction: Option<MovingDirection>,
+    repeat_in: f32,
+}
+
+/// The connected gamepads.
+#[derive(Debug, Default)]
+pub struct ConnectedGamepads(HashMap<Gamepad, HeldDirection>);
+
+/// This system saves the connected gamepads and shows a toast
+/// when a gamepad is connected or disconnected.
+pub fn gamepad_connections(
+    mut listener: ResMut<GamepadListener>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    mut gamepads: ResMut<ConnectedGamepads>,
+    locale: Res<Locale>,
+    mut toasts: ResMut<Events<ToastEvent>>,
+) {
+    for GamepadEvent(gamepad, event_type) in listener.reader.iter(&gamepad_events) {
+        match event_type {
+            GamepadEventType::Connected => {
+                gamepads.0.insert(*gamepad, HeldDirection::default());
+                toasts.send(ToastEvent::new(
+                    locale.render(
+                        &LocalizedText::new("gamepad-connected")
+                            .with_number("number", gamepad.0 as u64 + 1),
+                    ),
+                ));
+            }
+            GamepadEventType::Disconnected => {
+                gamepads.0.remove(gamepad);
+                toasts.send(ToastEvent::new(
+                    locale.render(
+                        &LocalizedText::new("gamepad-disconnected")
+                            .with_number("number", gamepad.0 as u64 + 1),
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the direction of the D-pad, or the direction
+/// of the left stick if it's out of the dead zone.
+fn held_direction(
+    gamepad: Gamepad,
+    settings: &GamepadInputSettings,
+    buttons: &Input<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+) -> Option<MovingDirection> {
+    let dpad = [
+        (GamepadButtonType::DPadLeft, MovingDirection::Left),
+        (GamepadButtonType::DPadUp, MovingDirection::Up),
+        (GamepadButtonType::DPadRight, MovingDirection::Right),
+        (GamepadButtonType::DPadDown, MovingDirection::Down),
+    ];
+
+    for &(button, direction) in dpad.iter() {
+        if buttons.pressed(GamepadButton(gamepad, button)) {
+            return Some(direction);
+        }
+    }
+
+    let x = axes
+        .get(GamepadAxis(gamepad, GamepadAxisType::LeftStickX))
+        .unwrap_or(0.0);
+    let y = axes
+        .get(GamepadAxis(gamepad, GamepadAxisType::LeftStickY))
+        .unwrap_or(0.0);
+
+    if Vec2::new(x, y).length() < settings.dead_zone {
+        None
+    } else if x.abs() > y.abs() {
+        Some(if x > 0.0 {
+            MovingDirection::Right
+        } else {
+            MovingDirection::Left
+        })
+    } else {
+        Some(if y > 0.0 {
+            MovingDirection::Up
+        } else {
+            MovingDirection::Down
+        })
+    }
+}
+
+/// Sends a moving input event when a gamepad chooses a direction,
+/// and repeats it while the direction is held.
+pub fn gamepad_moving_input(
+    time: Res<Time>,
+    settings: Res<GamepadInputSettings>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut gamepads: ResMut<ConnectedGamepads>,
+    mut moving_inputs: ResMut<Events<MovingInputEvent>>,
+) {
+    for (&gamepad, held) in gamepads.0.iter_mut() {
+        let direction = held_direction(gamepad, &settings, &buttons, &axes);
+
+        if direction != held.direction {
+            // A new direction is sent immediately.
+            held.direction = direction;
+            held.repeat_in = settings.repeat_delay;
+            if let Some(direction) = direction {
+                moving_inputs.send(MovingInputEvent { direction });
+            }
+        } else if let Some(direction) = direction {
+            held.repeat_in -= time.delta_seconds;
+            if held.repeat_in <= 0.0 {
+                held.repeat_in = settings.repeat_interval;
+                moving_inputs.send(MovingInputEvent { direction });
+            }
+        }
+    }
+}
+
+/// Restarts the game when Start is pressed on any gamepad.
+pub fn gamepad_new_game(
+    app_state: Res<AppState>,
+    mut game_state: ResMut<GameState>,
+    buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<ConnectedGamepads>,
+    settings: Res<Settings>,
+) {
+    if *app_state != AppState::Playing {
+        return;
+    }
+
+    for &gamepad in gamepads.0.keys() {
+        if buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::Start)) {
+            game_state.restart(&settings);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Headless parts of bevy_2048 that can be used without running the game.
+//!
+//! `rules` contains the game's rules and `env` contains a gym-style
+//! environment built on them, for training agents.
+//! `easing` contains the curves the game's animations progress along.
+
+pub mod easing;
+pub mod env;
+pub mod rules;
@@ -0,0 +1,317 @@
+//! This module contains the implementation of the localisation.
+//! A locale is loaded from `assets/locales/<language>.ron` and is chosen in the settings.
+//! A message that a locale is missing is looked up along its fallback chain,
+//! like `pt-BR`, then `pt`, then `en`, and the key itself is shown when no locale has it.
+//! Switching the language rewrites every `LocalizedText` that is already spawned.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::{collections::HashMap, env, error::Error, fs};
+
+use crate::{
+    common::{asset_names, asset_path, Settings},
+    ui::ErrorEvent,
+};
+
+/// The language every fallback chain ends with.
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// The locale used when the fallback language can't be loaded from the assets.
+const FALLBACK_LOCALE: &str = include_str!("../assets/locales/en.ron");
+
+/// This plugin builds the localisation into the app.
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<Locale>()
+            .add_system(switch_locale.system())
+            // The texts are spawned and changed during the update stage.
+            .add_system_to_stage(stage::POST_UPDATE, localize_texts.system())
+            .add_system_to_stage(stage::POST_UPDATE, relocalize_texts.system());
+    }
+}
+
+/// A locale's file, with the messages of a language.
+#[derive(Debug, Clone, Deserialize)]
+struct LocaleFile {
+    /// The name of the language that is shown to the player, in the language itself.
+    name: String,
+    /// The separator between the groups of three digits, like the comma in 16,384.
+    #[serde(default)]
+    group_separator: Option<String>,
+    /// The separator between the whole part and the fraction, like the dot in 1.5.
+    #[serde(default)]
+    decimal_separator: Option<String>,
+    /// The messages by their keys, `{name}` is replaced with the argument called `name`.
+    messages: HashMap<String, String>,
+}
+
+impl LocaleFile {
+    /// Loads the locale's file from `assets/locales/<language>.ron`.
+    fn load(language: &str) -> Result<Self, Box<dyn Error>> {
+        let source = fs::read_to_string(asset_path("locales", &format!("{}.ron", language)))?;
+        Ok(ron::de::from_str(&source)?)
+    }
+
+    /// Parses the embedded fallback locale.
+    fn fallback() -> Self {
+        ron::de::from_str(FALLBACK_LOCALE).expect("The fallback locale is valid")
+    }
+}
+
+/// The texts of the game in the chosen language.
+#[derive(Debug, Clone)]
+pub struct Locale {
+    /// The language of the locale's file, like `en` or `pt-BR`.
+    pub id: String,
+    /// The name of the language that is shown to the player.
+    pub name: String,
+    /// The locales a message is looked up in, from the chosen one to the fallback.
+    chain: Vec<LocaleFile>,
+}
+
+impl FromResources for Locale {
+    /// Loads the locale that is chosen in the settings.
+    /// If it fails it uses the fallback locale.
+    fn from_resources(resources: &Resources) -> Self {
+        let settings = resources.get::<Settings>().unwrap();
+
+        Self::load(&settings.language).unwrap_or_else(|e| {
+            eprintln!("Couldn't load locale `{}`: {}", settings.language, e);
+            Self::from_chain(FALLBACK_LANGUAGE, vec![LocaleFile::fallback()])
+        })
+    }
+}
+
+impl Locale {
+    /// Loads the locale of a language and the locales of its fallback chain.
+    /// Only the language's own file has to exist, the others are skipped when they are missing.
+    pub fn load(language: &str) -> Result<Self, Box<dyn Error>> {
+        let mut chain = vec![LocaleFile::load(language)?];
+        for fallback in fallback_chain(language).iter().skip(1) {
+            match LocaleFile::load(fallback) {
+                Ok(file) => chain.push(file),
+                Err(_) if fallback == FALLBACK_LANGUAGE => chain.push(LocaleFile::fallback()),
+                Err(_) => {}
+            }
+        }
+
+        Ok(Self::from_chain(language, chain))
+    }
+
+    fn from_chain(language: &str, chain: Vec<LocaleFile>) -> Self {
+        Self {
+            id: language.to_string(),
+            name: chain[0].name.clone(),
+            chain,
+        }
+    }
+
+    /// Returns the message of a key, or the key itself if no locale of the chain has it.
+    fn message<'a>(&'a self, key: &'a str) -> &'a str {
+        self.chain
+            .iter()
+            .find_map(|file| file.messages.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// Returns the first separator of the chain that is set, or the default one.
+    fn separator<'a>(
+        &'a self,
+        separator: impl Fn(&LocaleFile) -> &Option<String>,
+        default: &'a str,
+    ) -> &'a str {
+        self.chain
+            .iter()
+            .find_map(|file| separator(file).as_deref())
+            .unwrap_or(default)
+    }
+
+    /// Returns the message of a key without arguments.
+    pub fn text(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    /// Returns the message of a key with its arguments written in.
+    pub fn format(&self, key: &str, args: &[(String, LocaleArg)]) -> String {
+        let mut message = self.message(key).to_string();
+        for (name, arg) in args {
+            let value = match arg {
+                LocaleArg::Number(value) => self.number(*value),
+                LocaleArg::Decimal(value) => self.decimal(*value),
+                LocaleArg::Text(value) => value.clone(),
+                LocaleArg::Message(key) => self.text(key),
+            };
+            message = message.replace(&format!("{{{}}}", name), &value);
+        }
+
+        message
+    }
+
+    /// Returns the message of a localized text.
+    pub fn render(&self, text: &LocalizedText) -> String {
+        self.format(&text.key, &text.args)
+    }
+
+    /// Writes a whole number with its digits grouped by three, like 16,384.
+    pub fn number(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let separator = self.separator(|file| &file.group_separator, ",");
+
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// Writes a number with its fraction, like 1.5, without trailing zeros.
+    pub fn decimal(&self, value: f32) -> String {
+        let written = value.abs().to_string();
+        let (whole, fraction) = match written.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (written.as_str(), None),
+        };
+
+        let mut decimal = if value < 0.0 {
+            "-".to_string()
+        } else {
+            String::new()
+        };
+        decimal.push_str(&self.number(whole.parse().unwrap_or(0)));
+        if let Some(fraction) = fraction {
+            decimal.push_str(self.separator(|file| &file.decimal_separator, "."));
+            decimal.push_str(fraction);
+        }
+        decimal
+    }
+}
+
+/// An argument of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocaleArg {
+    /// A whole number, written with the locale's digit groups.
+    Number(u64),
+    /// A number with a fraction, written with the locale's separators.
+    Decimal(f32),
+    /// A text that is written as it is.
+    Text(String),
+    /// The key of another message, which is written in the same locale.
+    Message(String),
+}
+
+/// Component of a text that is written in the player's language.
+/// The text is rewritten when the component or the locale changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedText {
+    pub key: String,
+    pub args: Vec<(String, LocaleArg)>,
+}
+
+impl LocalizedText {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Adds a whole number argument.
+    pub fn with_number(self, name: &str, value: impl Into<u64>) -> Self {
+        self.with_arg(name, LocaleArg::Number(value.into()))
+    }
+
+    /// Adds a number argument with a fraction.
+    pub fn with_decimal(self, name: &str, value: f32) -> Self {
+        self.with_arg(name, LocaleArg::Decimal(value))
+    }
+
+    /// Adds an argument that is the message of another key.
+    pub fn with_message(self, name: &str, key: impl Into<String>) -> Self {
+        self.with_arg(name, LocaleArg::Message(key.into()))
+    }
+
+    /// Adds an argument that is written as it is.
+    pub fn with_text(self, name: &str, value: impl Into<String>) -> Self {
+        self.with_arg(name, LocaleArg::Text(value.into()))
+    }
+
+    fn with_arg(mut self, name: &str, arg: LocaleArg) -> Self {
+        self.args.push((name.to_string(), arg));
+        self
+    }
+}
+
+/// Returns the languages a message is looked up in, like `pt-BR`, `pt`, `en`.
+fn fallback_chain(language: &str) -> Vec<String> {
+    let mut chain = vec![language.to_string()];
+    if let Some((base, _)) = language.split_once('-') {
+        chain.push(base.to_string());
+    }
+    if !chain.iter().any(|language| language == FALLBACK_LANGUAGE) {
+        chain.push(FALLBACK_LANGUAGE.to_string());
+    }
+    chain
+}
+
+/// Lists the locales in `assets/locales`.
+pub fn available_locales() -> Vec<String> {
+    asset_names("locales", "ron")
+}
+
+/// Chooses the language of the system's `LANG`, like `de_DE.UTF-8`,
+/// if there is a locale for it or for its base language.
+/// Otherwise it chooses the fallback language.
+pub fn system_language() -> String {
+    let tag = env::var("LANG")
+        .unwrap_or_default()
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+
+    let available = available_locales();
+    fallback_chain(&tag)
+        .into_iter()
+        .find(|language| available.contains(language))
+        .unwrap_or_else(|| FALLBACK_LANGUAGE.to_string())
+}
+
+/// This system loads the locale when another language is chosen in the settings.
+fn switch_locale(
+    settings: ChangedRes<Settings>,
+    mut locale: ResMut<Locale>,
+    mut error_events: ResMut<Events<ErrorEvent>>,
+) {
+    if settings.language == locale.id {
+        return;
+    }
+
+    match Locale::load(&settings.language) {
+        Ok(loaded) => *locale = loaded,
+        Err(e) => error_events.send(ErrorEvent {
+            message: format!("Couldn't load locale `{}`: {}", settings.language, e),
+        }),
+    }
+}
+
+/// This system writes the texts that have been spawned or whose arguments have changed.
+fn localize_texts(locale: Res<Locale>, mut texts: Query<(Changed<LocalizedText>, &mut Text)>) {
+    for (localized, mut text) in texts.iter_mut() {
+        let value = locale.render(&localized);
+        if text.value != value {
+            text.value = value;
+        }
+    }
+}
+
+/// This system rewrites every text when the language changes.
+fn relocalize_texts(locale: ChangedRes<Locale>, mut texts: Query<(&LocalizedText, &mut Text)>) {
+    for (localized, mut text) in texts.iter_mut() {
+        text.value = locale.render(localized);
+    }
+}
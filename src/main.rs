@@ -1,16 +1,43 @@
+mod audio;
 mod board;
+mod bot;
+mod cli;
 mod common;
+mod gamepad;
+mod locale;
 mod movement;
+mod particles;
 mod score;
+mod scripting;
+mod session;
+mod skin;
+mod streaming;
+mod theme;
 mod tile_spawning;
 mod ui;
+mod window_settings;
 
-use bevy::{prelude::*, render::pass::ClearColor};
-use common::{GameSizePlugin, GameState, Tile};
-use movement::MovementPlugin;
+use audio::SoundPlugin;
+use bevy::prelude::*;
+use bot::BotPlugin;
+use cli::LaunchOptions;
+use common::{
+    update_game_clock, Action, AddSettingsSection, AppState, GameClock, GameEvent, GameRules,
+    GameSizePlugin, GameState, InputBindings, Settings, SettingsPlugin, Tile,
+};
+use gamepad::GamepadInputPlugin;
+use locale::LocalePlugin;
+use movement::{GameWon, InputQueue, MovementPlugin};
+use particles::ParticlesPlugin;
 use score::{Score, ScoreSystemPlugin};
+use scripting::ScriptingPlugin;
+use session::SessionPlugin;
+use skin::SkinPlugin;
+use streaming::{SpectatorPlugin, StreamPlugin};
+use theme::ThemePlugin;
 use tile_spawning::{Despawn, SpawnTileEvent, SpawnTilePlugin};
-use ui::UiPlugin;
+use ui::{BindingsScreen, UiPlugin};
+use window_settings::{window_descriptor, WindowSettingsPlugin};
 
 #[macro_use]
 extern crate savefile_derive;
@@ -19,35 +46,76 @@ extern crate savefile_derive;
 pub const STARTING_TILES: usize = 2;
 
 fn main() {
-    App::build()
-        // Set window title.
-        .add_resource(WindowDescriptor {
-            title: "Bevy 2048".to_string(),
-            ..Default::default()
-        })
+    let options = LaunchOptions::from_args();
+
+    // The window is created with the saved settings.
+    let settings = Settings::default();
+
+    let mut app = App::build();
+    app.add_resource(window_descriptor(&settings))
         .add_plugins(DefaultPlugins)
+        .add_resource(settings)
+        .add_plugin(SettingsPlugin)
+        .add_plugin(WindowSettingsPlugin)
+        // The theme's materials are used by the board and the ui.
+        .add_plugin(ThemePlugin)
+        .add_plugin(SkinPlugin)
+        .add_plugin(LocalePlugin)
+        .add_event::<GameEvent>()
         .add_plugin(GameSizePlugin)
         .add_plugin(SpawnTilePlugin)
         .add_plugin(MovementPlugin)
+        .add_plugin(ParticlesPlugin)
+        .add_plugin(SoundPlugin)
+        .add_plugin(GamepadInputPlugin)
         .add_plugin(ScoreSystemPlugin)
         .add_plugin(UiPlugin)
         .init_resource::<GameState>()
-        // Set background color.
-        .add_resource(ClearColor(Color::rgb_u8(250, 248, 239)))
+        .init_resource::<GameClock>()
+        .add_system_to_stage(stage::PRE_UPDATE, update_game_clock.system())
+        .init_resource::<GameRules>()
+        .add_settings_section::<InputBindings>()
+        // The mode's rules replace the classic rules.
+        .add_plugin(ScriptingPlugin(options.mode.clone()))
+        .add_plugin(SessionPlugin)
         .add_startup_system(setup.system())
         .add_startup_system(board::spawn_board.system())
-        .add_system(new_game.system())
-        .add_system(space_new_game.system())
-        .run();
+        .add_system(board::show_empty_tiles.system());
+
+    if let Some(address) = options.spectate {
+        // The board is controlled by the streamed game.
+        app.add_resource(AppState::Spectating)
+            .add_plugin(SpectatorPlugin(address));
+    } else {
+        // A game launched with a mode or a bot skips the main menu.
+        if options.mode.is_some() || options.bot_pipe.is_some() {
+            app.add_resource(AppState::Playing)
+                .add_startup_system(spawn_starting_tiles.system());
+        } else {
+            app.init_resource::<AppState>();
+        }
+
+        app.add_system(new_game.system())
+            .add_system(space_new_game.system());
+
+        if let Some(address) = options.stream {
+            app.add_plugin(StreamPlugin(address));
+        }
+
+        if let Some(command) = options.bot_pipe {
+            app.add_plugin(BotPlugin(command));
+        }
+    }
+
+    app.run();
 }
 
-fn setup(
-    mut commands: Commands,
-    mut spawn_tile_events: ResMut<Events<SpawnTileEvent>>,
-) {
+fn setup(mut commands: Commands) {
     // Camera.
     commands.spawn(Camera2dComponents::default());
+}
 
+fn spawn_starting_tiles(mut spawn_tile_events: ResMut<Events<SpawnTileEvent>>) {
     // Spawning tiles at the beginning.
     spawn_tile_events.send(SpawnTileEvent {
         count: STARTING_TILES,
@@ -58,7 +126,9 @@ fn new_game(
     mut commands: Commands,
     mut game_state: ResMut<GameState>,
     mut spawn_tile_events: ResMut<Events<SpawnTileEvent>>,
-    mut score: ResMut<Score>,
+    mut game_events: ResMut<Events<GameEvent>>,
+    (mut score, mut won): (ResMut<Score>, ResMut<GameWon>),
+    mut input_queue: ResMut<InputQueue>,
     tiles: Query<With<Tile, Entity>>,
 ) {
     if matches!(*game_state, GameState::Restarting) {
@@ -71,12 +141,27 @@ fn new_game(
         });
 
         score.0 = 0;
+        won.0 = false;
+        // Directions chosen in the last game shouldn't move the new one.
+        input_queue.clear();
         *game_state = GameState::Play;
+
+        game_events.send(GameEvent::NewGame);
+        game_events.send(GameEvent::ScoreChanged { score: 0 });
     }
 }
 
-fn space_new_game(mut game_state: ResMut<GameState>, keyboard: Res<Input<KeyCode>>) {
-    if keyboard.just_pressed(KeyCode::Space) {
-        *game_state = GameState::Restarting;
+fn space_new_game(
+    app_state: Res<AppState>,
+    mut game_state: ResMut<GameState>,
+    keyboard: Res<Input<KeyCode>>,
+    (bindings, settings): (Res<InputBindings>, Res<Settings>),
+    bindings_screen: Res<BindingsScreen>,
+) {
+    if *app_state == AppState::Playing
+        && !bindings_screen.open
+        && bindings.just_pressed(Action::Restart, &keyboard)
+    {
+        game_state.restart(&settings);
     }
 }
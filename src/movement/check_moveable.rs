@@ -2,7 +2,7 @@
 
 use bevy::prelude::*;
 
-use crate::common::{GameState, Position, Tile};
+use crate::common::{rules_board, GameEvent, GameRules, GameSize, GameState, Position, Tile};
 
 use super::MovingState;
 
@@ -11,51 +11,19 @@ use super::MovingState;
 pub fn check_moveable(
     mut game_state: ResMut<GameState>,
     mut moving_state: ResMut<MovingState>,
+    mut game_events: ResMut<Events<GameEvent>>,
+    game_size: Res<GameSize>,
+    rules: Res<GameRules>,
     tiles: Query<(&Tile, &Position)>,
 ) {
-    if matches!(*game_state, GameState::Play) {
-        if matches!(*moving_state, MovingState::CheckingMoveable) {
-            // Checking if the board is full.
-            let len = tiles.iter().len();
-            if len == 16 {
-                // Creating a 1d array board.
-                let mut iter = tiles.iter();
-                let null_tile = Tile { level: 0 };
-                let mut board = [&null_tile; 16];
-                for (tile, position) in &mut iter {
-                    board[position.index()] = tile;
-                }
-
-                // Checking if there are some neighbor tiles with the same level.
-                let mut gameover = true;
-                for row in 0..4 {
-                    for col in 0..4 {
-                        let pos = Position { row, col };
-
-                        if row < 3 {
-                            let up = Position { row: row + 1, col };
-                            if board[pos.index()].level == board[up.index()].level {
-                                gameover = false;
-                                break;
-                            }
-                        }
-
-                        if col < 3 {
-                            let right = Position { row, col: col + 1 };
-                            if board[pos.index()].level == board[right.index()].level {
-                                gameover = false;
-                                break;
-                            }
-                        }
-                    }
-                }
-
-                if gameover {
-                    *game_state = GameState::GameOver;
-                }
-            }
-
-            *moving_state = MovingState::Idle;
+    if matches!(*game_state, GameState::Play)
+        && matches!(*moving_state, MovingState::CheckingMoveable)
+    {
+        if rules.is_game_over(&rules_board(game_size.cells(), tiles.iter())) {
+            *game_state = GameState::GameOver;
+            game_events.send(GameEvent::GameOver);
         }
+
+        *moving_state = MovingState::Idle;
     }
 }
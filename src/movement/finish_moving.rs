@@ -2,22 +2,32 @@
 
 use bevy::prelude::*;
 
-use crate::tile_spawning::SpawnTileEvent;
+use crate::{
+    common::{GameEvent, GameRules},
+    score::Score,
+    tile_spawning::SpawnTileEvent,
+};
 
-use super::{Merged, MovingState};
+use super::{MovePlan, MovingState};
 
-/// When the moving state is `Finishing`, removing set all merged to `None`
-/// and spawn a new tile.
+/// When the moving state is `Finishing`, let the rules know the move
+/// has finished and spawn a new tile.
 pub fn finish_moving(
     mut moving_state: ResMut<MovingState>,
     mut spawn_tile_events: ResMut<Events<SpawnTileEvent>>,
-    mut merged: Query<&mut Option<Merged>>,
+    mut game_events: ResMut<Events<GameEvent>>,
+    (rules, move_plan): (Res<GameRules>, Res<MovePlan>),
+    mut score: ResMut<Score>,
 ) {
     if let MovingState::Finishing { moved } = *moving_state {
-        // Setting all the merged to `None`.
-        for mut merged in merged.iter_mut() {
-            if merged.is_some() {
-                *merged = None;
+        // Adding the bonus of the rules to the score.
+        if moved {
+            // The merged tiles are despawned at the end of the frame,
+            // so the board is taken from the move.
+            let bonus = rules.on_move_finished(&move_plan.board, score.0);
+            if bonus > 0 {
+                score.0 = score.0.saturating_add(bonus);
+                game_events.send(GameEvent::ScoreChanged { score: score.0 });
             }
         }
 
@@ -0,0 +1,198 @@
+//! This module contains the implementation of the mouse drag and touch swipe input.
+use bevy::{
+    input::touch::{TouchInput, TouchPhase},
+    prelude::*,
+    utils::HashMap,
+    window::CursorMoved,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{MovingDirection, MovingInputEvent};
+use crate::common::{GameSize, SettingsSection};
+
+/// The shortest and longest distances a gesture can be set to pass, and the step between them.
+const MIN_DISTANCES: (f32, f32, f32) = (10.0, 100.0, 10.0);
+
+/// The narrowest and widest angle tolerances, and the step between them.
+/// A tolerance above 45 degrees would let a gesture count for two directions.
+const ANGLE_TOLERANCES: (f32, f32, f32) = (15.0, 45.0, 5.0);
+
+/// Moves a value a step up or down within `(min, max, step)`, rounded to the steps.
+fn step_within(value: f32, up: bool, (min, max, step): (f32, f32, f32)) -> f32 {
+    let steps = (value / step).round() + if up { 1.0 } else { -1.0 };
+    (steps * step).clamp(min, max)
+}
+
+/// The settings of the gestures that move the tiles,
+/// saved as the `gestures` section of the settings.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GestureSettings {
+    /// The distance in pixels a gesture should pass to be counted.
+    pub min_distance: f32,
+    /// How many degrees a gesture can be away from a direction.
+    pub angle_tolerance: f32,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            min_distance: 30.0,
+            angle_tolerance: 30.0,
+        }
+    }
+}
+
+impl SettingsSection for GestureSettings {
+    const NAME: &'static str = "gestures";
+
+    fn defaults() -> Self {
+        Self::default()
+    }
+
+    /// Values that were edited in the file are kept within the choices.
+    fn complete(&mut self) {
+        self.min_distance = self.min_distance.clamp(MIN_DISTANCES.0, MIN_DISTANCES.1);
+        self.angle_tolerance = self
+            .angle_tolerance
+            .clamp(ANGLE_TOLERANCES.0, ANGLE_TOLERANCES.1);
+    }
+}
+
+impl GestureSettings {
+    /// Moves the distance a gesture should pass a step up or down.
+    pub fn step_min_distance(&mut self, up: bool) {
+        self.min_distance = step_within(self.min_distance, up, MIN_DISTANCES);
+    }
+
+    /// Moves the angle tolerance a step up or down.
+    pub fn step_angle_tolerance(&mut self, up: bool) {
+        self.angle_tolerance = step_within(self.angle_tolerance, up, ANGLE_TOLERANCES);
+    }
+
+    /// Returns the direction of a gesture, from its start to its end.
+    /// Returns `None` if the gesture is too short or too far from every direction.
+    pub fn direction(&self, start: Vec2, end: Vec2) -> Option<MovingDirection> {
+        let delta = end - start;
+        if delta.length() < self.min_distance {
+            return None;
+        }
+
+        // The angle from the right direction, counterclockwise in [-180, 180].
+        let angle = delta.y().atan2(delta.x()).to_degrees();
+        let (direction, axis) = if angle.abs() <= 45.0 {
+            (MovingDirection::Right, 0.0)
+        } else if angle.abs() >= 135.0 {
+            (MovingDirection::Left, 180.0 * angle.signum())
+        } else if angle > 0.0 {
+            (MovingDirection::Up, 90.0)
+        } else {
+            (MovingDirection::Down, -90.0)
+        };
+
+        if (angle - axis).abs() <= self.angle_tolerance {
+            Some(direction)
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts a window position with the origin at the bottom left into a world position.
+/// The camera is at the center of the window.
+fn to_world(windows: &Windows, position: Vec2) -> Vec2 {
+    let window = windows.get_primary().unwrap();
+    position - Vec2::new(window.width() as f32, window.height() as f32) / 2.0
+}
+
+/// Checks if a world position is on the board.
+fn on_board(game_size: &GameSize, position: Vec2) -> bool {
+    let half = game_size.board_size() / 2.0;
+    position.x().abs() <= half && position.y().abs() <= half
+}
+
+/// The state of the mouse drag.
+#[derive(Default)]
+pub struct MouseGesture {
+    cursor_reader: EventReader<CursorMoved>,
+    cursor: Vec2,
+    /// Where the drag has started, `None` if the left button isn't held on the board.
+    start: Option<Vec2>,
+}
+
+/// Dragging the mouse over the board and releasing
+/// the left button sends a moving input event.
+pub fn mouse_input(
+    mut state: Local<MouseGesture>,
+    settings: Res<GestureSettings>,
+    game_size: Res<GameSize>,
+    windows: Res<Windows>,
+    cursor_moved: Res<Events<CursorMoved>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut moving_inputs: ResMut<Events<MovingInputEvent>>,
+) {
+    if let Some(event) = state.cursor_reader.latest(&cursor_moved) {
+        state.cursor = to_world(&windows, event.position);
+    }
+
+    if mouse_buttons.just_pressed(MouseButton::Left) && on_board(&game_size, state.cursor) {
+        state.start = Some(state.cursor);
+    }
+
+    if mouse_buttons.just_released(MouseButton::Left) {
+        if let Some(start) = state.start.take() {
+            if let Some(direction) = settings.direction(start, state.cursor) {
+                moving_inputs.send(MovingInputEvent { direction });
+            }
+        }
+    }
+}
+
+/// The state of the touch swipes.
+#[derive(Default)]
+pub struct TouchGesture {
+    reader: EventReader<TouchInput>,
+    /// Where each finger on the board has started.
+    starts: HashMap<u64, Vec2>,
+}
+
+/// Swiping a finger over the board sends a moving input event.
+pub fn touch_input(
+    mut state: Local<TouchGesture>,
+    settings: Res<GestureSettings>,
+    game_size: Res<GameSize>,
+    windows: Res<Windows>,
+    touches: Res<Events<TouchInput>>,
+    mut moving_inputs: ResMut<Events<MovingInputEvent>>,
+) {
+    let window_height = windows.get_primary().unwrap().height() as f32;
+    let state = &mut *state;
+
+    for touch in state.reader.iter(&touches) {
+        // The origin of touches is at the top left, except on android where bevy flips it.
+        let mut position = touch.position;
+        if !cfg!(target_os = "android") {
+            *position.y_mut() = window_height - position.y();
+        }
+        let position = to_world(&windows, position);
+
+        match touch.phase {
+            TouchPhase::Started => {
+                if on_board(&game_size, position) {
+                    state.starts.insert(touch.id, position);
+                }
+            }
+            TouchPhase::Ended => {
+                if let Some(start) = state.starts.remove(&touch.id) {
+                    if let Some(direction) = settings.direction(start, position) {
+                        moving_inputs.send(MovingInputEvent { direction });
+                    }
+                }
+            }
+            TouchPhase::Cancelled => {
+                state.starts.remove(&touch.id);
+            }
+            TouchPhase::Moved => {}
+        }
+    }
+}
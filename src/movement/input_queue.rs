@@ -0,0 +1,76 @@
+//! This module contains the implementation of the InputQueue resource.
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::MovingDirection;
+use crate::common::SettingsSection;
+
+/// The deepest queue that can be chosen in the settings.
+pub const MAX_QUEUE_DEPTH: usize = 8;
+
+/// The settings of the input queue, saved as the `input_queue` section of the settings.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputQueueSettings {
+    /// The number of directions that can wait while moving.
+    /// Directions that don't fit are dropped.
+    pub depth: usize,
+    /// Tells if a direction that is the same as the last queued direction is dropped.
+    pub collapse_duplicates: bool,
+    /// Tells if the moving animation is skipped while directions are waiting.
+    pub fast_forward: bool,
+}
+
+impl Default for InputQueueSettings {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            collapse_duplicates: false,
+            fast_forward: false,
+        }
+    }
+}
+
+impl SettingsSection for InputQueueSettings {
+    const NAME: &'static str = "input_queue";
+
+    fn defaults() -> Self {
+        Self::default()
+    }
+
+    /// A depth that was edited in the file is kept within the choices.
+    fn complete(&mut self) {
+        self.depth = self.depth.min(MAX_QUEUE_DEPTH);
+    }
+}
+
+/// The directions that were chosen while the tiles were moving,
+/// waiting to be moved to in order.
+#[derive(Debug, Default)]
+pub struct InputQueue(VecDeque<MovingDirection>);
+
+impl InputQueue {
+    /// Adds a direction to the end of the queue,
+    /// unless the queue is full or it's a collapsed duplicate.
+    pub fn push(&mut self, direction: MovingDirection, settings: &InputQueueSettings) {
+        let full = self.0.len() >= settings.depth;
+        let duplicate = settings.collapse_duplicates && self.0.back() == Some(&direction);
+
+        if !full && !duplicate {
+            self.0.push_back(direction);
+        }
+    }
+
+    /// Removes the first direction of the queue.
+    pub fn pop(&mut self) -> Option<MovingDirection> {
+        self.0.pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
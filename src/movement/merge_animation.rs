@@ -1,7 +1,8 @@
 //! This module contains the implementation of the MergeAnimation component and system.
 
-use crate::common::{Animation, GameSize};
+use crate::common::{Animation, GameClock, GameSize};
 use bevy::prelude::*;
+use bevy_2048::easing::Easing;
 
 /// Component used to animate the tiles that have been merged.
 pub struct MergeAnimation {
@@ -9,12 +10,10 @@ pub struct MergeAnimation {
 }
 
 impl Default for MergeAnimation {
-    /// Sets the animation to finish after 8
+    /// Sets the animation to grow the tile and shrink it back, in 0.14 seconds.
     fn default() -> Self {
-        let func = |x| 4.0 * x * (1.0 - x);
-
         Self {
-            animation: Animation::with_func(8, func),
+            animation: Animation::new(0.07, Easing::OutCubic).then(0.0, 0.07, Easing::InCubic),
         }
     }
 }
@@ -22,13 +21,13 @@ impl Default for MergeAnimation {
 /// Animating all the tiles that have been merged.
 pub fn merge_animation(
     mut commands: Commands,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     game_size: Res<GameSize>,
     entity: Entity,
     mut merge_anim: Mut<MergeAnimation>,
     mut sprite: Mut<Sprite>,
 ) {
-    if merge_anim.animation.update(time.delta_seconds) {
+    if merge_anim.animation.update(clock.animation_seconds) {
         // Updating the sprite size while the animation is not finished.
         let size = game_size.tile_size() + game_size.merge_size() * merge_anim.animation.value();
         sprite.size.set_x(size);
@@ -3,60 +3,104 @@
 use bevy::prelude::*;
 
 use crate::{
-    common::{Position, Tile},
+    common::{GameEvent, GameRules, GameSize, Tile},
+    particles::Emitter,
     score::Score,
+    theme::Theme,
     tile_spawning::Despawn,
 };
 
-use super::{MergeAnimation, Merged, MovingState};
+use super::{MergeAnimation, MovePlan, MovingState};
 
-/// When the moving state is `Merging`, it merging tiles
-/// that are in the same position.
+/// The lowest level that is celebrated when it's the highest tile of the game.
+const CELEBRATED_LEVEL: u32 = 6;
+
+/// Tells if the winning tile has been reached in this game, so the win is celebrated once.
+#[derive(Debug, Default)]
+pub struct GameWon(pub bool);
+
+/// When the moving state is `Merging`, merging the tiles
+/// that have slid into each other.
+/// Particles burst out of the merged tiles, a new highest tile is celebrated
+/// and confetti falls when the rules' winning tile is reached, even by a merge
+/// that removes the tiles.
+#[allow(clippy::too_many_arguments)]
 pub fn merging(
     mut commands: Commands,
     mut moving_state: ResMut<MovingState>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut score: ResMut<Score>,
-    mut tiles: Query<(
-        Entity,
-        &mut Tile,
-        &Position,
-        &mut Option<Merged>,
-        &mut Handle<ColorMaterial>,
-    )>,
+    mut move_plan: ResMut<MovePlan>,
+    (theme, game_size, rules): (Res<Theme>, Res<GameSize>, Res<GameRules>),
+    (mut score, mut won): (ResMut<Score>, ResMut<GameWon>),
+    mut game_events: ResMut<Events<GameEvent>>,
+    mut tiles: Query<&mut Tile>,
 ) {
     if matches!(*moving_state, MovingState::Merging) {
-        // Create a board with entity and position to check
-        // if two tiles are at the same position.
-        let mut board = [None; 16];
-        for (entity, mut tile, position, mut merged, mut material) in tiles.iter_mut() {
-            // Check if a tile is already exists at that position.
-            if let Some((existing_entity, _position)) = board[position.index()] {
-                // Despawning the existing tile.
-                commands.despawn(existing_entity);
-
-                // Checking that the level is not the last one.
-                if tile.level < 9 {
-                    // Updating current tile level and color.
-                    tile.level += 1;
-                    *material = materials.add(tile.color().into());
+        let mut highest = tiles.iter_mut().map(|tile| tile.level).max();
+
+        for merge in move_plan.merges.drain(..) {
+            let into = tiles.get_mut(merge.into).ok().map(|tile| tile.level);
+            let from = tiles.get_mut(merge.from).ok().map(|tile| tile.level);
+
+            // Despawning the tile that has been merged into the other one.
+            commands.despawn(merge.from);
+
+            game_events.send(GameEvent::Merged {
+                position: merge.position,
+                level: merge.level,
+            });
+
+            let origin = merge.position.to_vec3(*game_size);
+            let wins = match (into, from) {
+                (Some(into), Some(from)) => {
+                    !won.0 && rules.is_winning_merge(into, from, merge.level)
+                }
+                _ => false,
+            };
+            if wins {
+                won.0 = true;
+                let top = merge.level.or(into).unwrap_or_default();
+                let colors = (0..=top).map(|l| theme.tile_color(l)).collect();
+                commands.spawn((Emitter::confetti(&game_size, colors),));
+                game_events.send(GameEvent::Won);
+            }
+
+            // Checking that the merged tile should stay on the board.
+            if let Some(level) = merge.level {
+                // Updating the tile's level, its color is updated by the theme.
+                if let Ok(mut tile) = tiles.get_mut(merge.into) {
+                    tile.level = level;
 
                     // Updating the score.
-                    score.0 += tile.score();
+                    score.0 = score.0.saturating_add(tile.score());
+                    game_events.send(GameEvent::ScoreChanged { score: score.0 });
+                }
 
-                    // Setting the tile as merged.
-                    *merged = Some(Merged);
-                    commands.insert_one(entity, MergeAnimation::default());
+                commands.insert_one(merge.into, MergeAnimation::default());
+
+                let color = theme.tile_color(level);
+                let new_highest = highest.is_none_or(|highest| level > highest);
+                if new_highest {
+                    highest = Some(level);
+                }
+
+                let emitter = if wins || (new_highest && level >= CELEBRATED_LEVEL) {
+                    Emitter::celebration(origin, color)
                 } else {
-                    // If the level is the last, despawn the tile with an animation.
-                    commands.insert_one(entity, Despawn);
+                    Emitter::merge_burst(origin, color)
+                };
+                commands.spawn((emitter,));
+            } else {
+                if let Some(level) = into {
+                    if wins {
+                        commands.spawn((Emitter::celebration(origin, theme.tile_color(level)),));
+                    }
                 }
-            }
 
-            // Move the tile into the board.
-            board[position.index()] = Some((entity, position));
+                // If the merged tile is removed, despawn it with an animation.
+                commands.insert_one(merge.into, Despawn);
+            }
         }
 
-        *moving_state = MovingState::SetMoving { starting: false };
+        *moving_state = MovingState::Finishing { moved: true };
     }
 }
@@ -2,6 +2,8 @@
 
 mod check_moveable;
 mod finish_moving;
+mod gesture_input;
+mod input_queue;
 mod merge_animation;
 mod merging;
 mod moving_animation;
@@ -10,25 +12,41 @@ mod moving_input;
 mod moving_state;
 mod set_moving;
 
+pub use gesture_input::GestureSettings;
+pub use input_queue::{InputQueue, InputQueueSettings, MAX_QUEUE_DEPTH};
 pub use merge_animation::MergeAnimation;
-pub use moving_animation::MovingAnimation;
+pub use merging::GameWon;
 pub use moving_direction::MovingDirection;
+pub use moving_input::MovingInputEvent;
 pub use moving_state::MovingState;
-/// Component to tell if a tile is moving or not.
-pub struct Moving;
-
-/// Component to tell if a tile has been merged or not.
-pub struct Merged;
+pub use set_moving::MovePlan;
 
+use crate::common::{AddSettingsSection, Animation, Position};
 use bevy::prelude::*;
+
+/// Component to tell if a tile is moving or not.
+/// A moving tile slides from its position to `to` along its animation.
+pub struct Moving {
+    pub to: Position,
+    pub animation: Animation,
+}
+
 pub struct MovementPlugin;
 
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut bevy::prelude::AppBuilder) {
-        app.init_resource::<MovingAnimation>()
+        app.init_resource::<MovePlan>()
             .init_resource::<MovingState>()
-            .init_resource::<Option<MovingDirection>>()
+            .init_resource::<GameWon>()
+            .init_resource::<InputQueue>()
+            .add_settings_section::<InputQueueSettings>()
             .add_resource(MovingDirection::Left)
+            .init_resource::<moving_input::MovingInputListener>()
+            .add_event::<MovingInputEvent>()
+            .add_settings_section::<GestureSettings>()
+            .add_system(moving_input::keyboard_input.system())
+            .add_system(gesture_input::mouse_input.system())
+            .add_system(gesture_input::touch_input.system())
             .add_system(moving_input::moving_input.system())
             .add_system(moving_input::next_direction.system())
             .add_system(set_moving::set_moving.system())
@@ -1,69 +1,55 @@
-//! This module contains the implementation of the MovingAnimation component and
-//! the implementation of the Animating state's system.
+//! This module contains the implementation of the Animating state's system.
 use bevy::prelude::*;
 
-use crate::common::{Animation, GameSize, Position};
+use crate::common::{GameClock, GameSize, Position};
 
-use super::{Moving, MovingDirection, MovingState};
+use super::{InputQueue, InputQueueSettings, Moving, MovingState};
 
-/// Animating the movement of the tiles.
-/// This is a global resource because all tiles
-/// should be animated the same time.
-pub struct MovingAnimation {
-    pub animation: Animation,
-}
-
-impl Default for MovingAnimation {
-    /// Sets the animation to finish after 3 updates.
-    fn default() -> Self {
-        Self {
-            animation: Animation::new(3),
-        }
-    }
-}
-
-/// While the moving state is `Animating`, animating all moving tiles.
+/// While the moving state is `Animating`, sliding every moving tile from its position
+/// to its destination. Each tile has its own animation, so the tiles that slide
+/// farther take longer. If fast forward is enabled and directions are queued,
+/// the animations are skipped.
 pub fn moving_animation(
-    time: Res<Time>,
+    clock: Res<GameClock>,
     game_size: Res<GameSize>,
     mut moving_state: ResMut<MovingState>,
-    mut moving_anim: ResMut<MovingAnimation>,
-    moving_dir: Res<MovingDirection>,
-    mut queries: QuerySet<(
-        // Used for animating.
-        Query<(&Position, &mut Transform, &Option<Moving>)>,
-        // Used for updating the position at the end.
-        Query<(&mut Position, &mut Option<Moving>)>,
-    )>,
+    (input_queue, queue_settings): (Res<InputQueue>, Res<InputQueueSettings>),
+    mut tiles: Query<(&mut Position, &mut Transform, &mut Option<Moving>)>,
 ) {
     if matches!(*moving_state, MovingState::Animating) {
-        // Checking if should update the transform of the tiles.
-        if moving_anim.animation.update(time.delta_seconds) {
-            // For each tile that is moving, update its transform.
-            for (position, mut transform, moving) in queries.q0_mut().iter_mut() {
-                if moving.is_some() {
-                    // The amount to move from its position.
-                    let translate: Vec3 = Vec3::from(*moving_dir)
-                        * (game_size.tile_size() + game_size.tile_spacing())
-                        * moving_anim.animation.value();
-
-                    // update the transform.
-                    transform.translation = position.to_vec3(*game_size) + translate;
+        let skip = queue_settings.fast_forward && !input_queue.is_empty();
+        let mut animating = false;
+
+        for (mut position, mut transform, mut moving) in tiles.iter_mut() {
+            let finished = match &mut *moving {
+                Some(moving) => {
+                    if skip {
+                        moving.animation.skip();
+                    }
+
+                    // Updating the transform between the start and the destination.
+                    if moving.animation.update(clock.animation_seconds) {
+                        let from = position.to_vec3(*game_size);
+                        let to = moving.to.to_vec3(*game_size);
+                        transform.translation = from + (to - from) * moving.animation.value();
+                    }
+
+                    moving.animation.finished()
                 }
+                None => continue,
+            };
+
+            // When the tile has arrived, its position is updated.
+            if finished {
+                *position = moving.as_ref().unwrap().to;
+                transform.translation = position.to_vec3(*game_size);
+                *moving = None;
+            } else {
+                animating = true;
             }
         }
 
-        // If the animation have been finished, remove all moving and
-        // update the position component.
-        if moving_anim.animation.finished() {
-            for (mut position, mut moving) in queries.q1_mut().iter_mut() {
-                if moving.is_some() {
-                    *position = moving_dir.moved_position(&position).unwrap();
-                    *moving = None;
-                }
-            }
-
-            moving_anim.animation.reset();
+        if !animating {
             *moving_state = MovingState::Merging;
         }
     }
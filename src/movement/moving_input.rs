@@ -2,52 +2,95 @@
 use bevy::prelude::*;
 use std::convert::TryFrom;
 
-use crate::common::GameState;
-use super::{MovingDirection, MovingState};
+use super::{InputQueue, InputQueueSettings, MovingDirection, MovingState};
+use crate::{
+    common::{AppState, GameState, InputBindings},
+    ui::{BindingsScreen, SettingsScreen},
+};
+
+/// Event sent by the input sources when a direction has been chosen.
+pub struct MovingInputEvent {
+    pub direction: MovingDirection,
+}
+
+/// Event listener for MovingInputEvent.
+#[derive(Default)]
+pub struct MovingInputListener {
+    pub reader: EventReader<MovingInputEvent>,
+}
+
+/// If the user pressed a key bound to a moving action,
+/// a moving input event is being sent.
+pub fn keyboard_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<InputBindings>,
+    mut moving_inputs: ResMut<Events<MovingInputEvent>>,
+) {
+    // Iterating through the keys that were just pressed by the user.
+    for &key in keyboard_input.get_just_pressed() {
+        // Checking if the actions of the key can be converted into a direction
+        for action in bindings.actions(key) {
+            if let Ok(direction) = MovingDirection::try_from(action) {
+                moving_inputs.send(MovingInputEvent { direction });
+            }
+        }
+    }
+}
 
 /// While the moving state is `Idle`, getting the input
 /// of the user.
-/// If the user pressed the arrows or a,w,d,s keys,
-/// the direction is being chosen
+/// If a moving input event was sent, the direction is being chosen.
+#[allow(clippy::too_many_arguments)]
 pub fn moving_input(
+    app_state: Res<AppState>,
     game_state: Res<GameState>,
-    keyboard_input: Res<Input<KeyCode>>,
+    (bindings_screen, settings_screen): (Res<BindingsScreen>, Res<SettingsScreen>),
+    mut listener: ResMut<MovingInputListener>,
+    moving_inputs: Res<Events<MovingInputEvent>>,
     mut moving_state: ResMut<MovingState>,
     mut moving_dir: ResMut<MovingDirection>,
-    mut next_dir: ResMut<Option<MovingDirection>>,
+    mut input_queue: ResMut<InputQueue>,
+    queue_settings: Res<InputQueueSettings>,
 ) {
-    if matches!(*game_state, GameState::Play) {
-        // Iterating through the keys that were just pressed by the user.
-        for key in keyboard_input.get_just_pressed() {
-            // Checking if the keys can be converted into a direction
-            if let Ok(direction) = MovingDirection::try_from(key) {
-                if matches!(*moving_state, MovingState::Idle) {
-                    // Setting the direction.
-                    *moving_dir = direction;
-                    // Setting the moving state to `SetMoving` with starting.
-                    *moving_state = MovingState::SetMoving { starting: true };
-                } else {
-                    // If in the middle of moving, save the next direction.
-                    *next_dir = Some(direction);
-                }
+    // Reading the events even when they are ignored, so they won't be handled later.
+    let inputs = listener.reader.iter(&moving_inputs);
+
+    if *app_state == AppState::Playing
+        && matches!(*game_state, GameState::Play)
+        && !bindings_screen.open
+        && !settings_screen.open
+    {
+        for &MovingInputEvent { direction } in inputs {
+            if matches!(*moving_state, MovingState::Idle) {
+                // Setting the direction.
+                *moving_dir = direction;
+                // Setting the moving state to `SetMoving`.
+                *moving_state = MovingState::SetMoving;
+            } else {
+                // If in the middle of moving, queue the direction.
+                input_queue.push(direction, &queue_settings);
             }
         }
     }
 }
 
-/// This system checks whether the game is idle and there is a next direction to move.
+/// This system checks whether the game is idle and there is a queued direction to move.
+/// The queued directions wait while the game is paused or the main menu is shown.
 pub fn next_direction(
+    app_state: Res<AppState>,
+    game_state: Res<GameState>,
     mut moving_state: ResMut<MovingState>,
     mut moving_dir: ResMut<MovingDirection>,
-    mut next_dir: ResMut<Option<MovingDirection>>,
+    mut input_queue: ResMut<InputQueue>,
 ) {
-    if matches!(*moving_state, MovingState::Idle) {
-        if let Some(direction) = *next_dir {
-            *next_dir = None;
-
+    if *app_state == AppState::Playing
+        && matches!(*moving_state, MovingState::Idle)
+        && !game_state.is_paused()
+    {
+        if let Some(direction) = input_queue.pop() {
             // Moving to the next direction.
             *moving_dir = direction;
-            *moving_state = MovingState::SetMoving { starting: true };
+            *moving_state = MovingState::SetMoving;
         }
     }
 }
@@ -5,29 +5,22 @@
 #[derive(Debug)]
 pub enum MovingState {
     /// This is the default state, when no moving is happening.
-    /// When should move the next state is `SetMoving` with
-    /// `starting` set to `true`.
+    /// When should move the next state is `SetMoving`.
     Idle,
-    /// At this state, checking which tile should move.
-    /// When done checking, if some tiles should move,
-    /// the next state is `Animating`
-    /// otherwise, the next state is `Finishing` with
-    /// `moved` set to `!starting`.
-    SetMoving {
-        /// Tells if this is the first time checking for moving tiles.
-        starting: bool,
-    },
+    /// At this state, the whole move is calculated at once:
+    /// where each tile ends and which tiles are merged.
+    /// When done, if some tiles should move, the next state is `Animating`
+    /// otherwise, the next state is `Finishing` with `moved` set to `false`.
+    SetMoving,
     /// While at this state, all the tiles that should move are
-    /// sliding in the moving direction.
+    /// sliding from their start to their destination.
     /// When done animating, the next state is `Merging`.
     Animating,
-    /// At this state, checking each tiles that are at the same position,
-    /// Are being merged.
-    /// Then setting the next state to `SetMoving` with `starting` set to `false`.
+    /// At this state, the tiles that have slid into each other are being merged.
+    /// Then setting the next state to `Finishing` with `moved` set to `true`.
     Merging,
     /// At this state, all the tiles are at their final position.
-    /// Removing the merged compoent from the tiles and spawning a new
-    /// tile if `moved` is `true`.
+    /// Spawning a new tile if `moved` is `true`.
     /// When done, the next state is `CheckingMoveable`.
     Finishing {
         /// Tells if any tile have been moved.
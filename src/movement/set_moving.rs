@@ -1,69 +1,140 @@
-//! This module contains the implementation of the SetMoving state's system.
+//! This module contains the implementation of the MovePlan resource and
+//! the implementation of the SetMoving state's system.
 
-use crate::common::{Position, Tile};
+use crate::common::{rules_board, Animation, GameEvent, GameRules, GameSize, Position, Tile};
 use bevy::prelude::*;
+use bevy_2048::{easing::Easing, rules::Board};
 
-use super::{Merged, Moving, MovingDirection, MovingState};
+use super::{Moving, MovingDirection, MovingState};
 
-// When the moving state is `SetMoving`, it checks which tile should move.
+/// The seconds a tile takes to slide a single cell.
+const SLIDE_SECONDS_PER_CELL: f32 = 0.04;
+
+/// A merge of two tiles that have slid into the same cell.
+#[derive(Debug)]
+pub struct PlannedMerge {
+    /// The tile that stays on the board.
+    pub into: Entity,
+    /// The tile that is merged into the other one.
+    pub from: Entity,
+    pub position: Position,
+    /// The level of the merged tile, `None` if it's removed from the board.
+    pub level: Option<u32>,
+}
+
+/// The move that is being animated, calculated at once when it starts.
+/// The merges are done when the tiles have arrived.
+#[derive(Debug)]
+pub struct MovePlan {
+    pub merges: Vec<PlannedMerge>,
+    /// The board after the move.
+    pub board: Board,
+}
+
+impl Default for MovePlan {
+    /// Creates a plan without a move.
+    fn default() -> Self {
+        Self {
+            merges: Vec::new(),
+            board: Board::new(0),
+        }
+    }
+}
+
+/// When the moving state is `SetMoving`, it calculates where each tile ends and which
+/// tiles are merged, and sets every tile that moves to slide to its destination.
 pub fn set_moving(
-    mut commands: Commands,
     mut moving_state: ResMut<MovingState>,
     moving_dir: Res<MovingDirection>,
-    tiles: Query<(Entity, &Tile, &Position, &Option<Moving>, &Option<Merged>)>,
+    (game_size, rules): (Res<GameSize>, Res<GameRules>),
+    mut move_plan: ResMut<MovePlan>,
+    mut game_events: ResMut<Events<GameEvent>>,
+    mut tiles: Query<(Entity, &Tile, &Position, &mut Option<Moving>)>,
 ) {
-    // Checking the moving state.
-    if let MovingState::SetMoving { starting } = *moving_state {
-        // Creating a board represented by a 1D array
-        // in order to check the neighbors tiles.
-        let mut tiles = tiles.iter();
-        let mut board: [Option<(Entity, &Tile, &Position, &Option<Moving>, &Option<Merged>)>; 16] =
-            Default::default();
-        for tile in &mut tiles {
-            let position = tile.2;
-            board[position.index()] = Some(tile);
+    if matches!(*moving_state, MovingState::SetMoving) {
+        let cells = game_size.cells();
+        let board = rules_board(cells, tiles.iter_mut().map(|(_, t, p, _)| (t, p)));
+        let result = board.slide_with((*moving_dir).into(), &**rules);
+
+        if !result.moved {
+            game_events.send(GameEvent::Blocked {
+                direction: *moving_dir,
+            });
+            *moving_state = MovingState::Finishing { moved: false };
+            return;
         }
 
-        // Vec of all the entities that should move.
-        let mut moving_entities = Vec::new();
+        // Telling that a new move has started.
+        game_events.send(GameEvent::Slid {
+            direction: *moving_dir,
+        });
+
+        // The entities of the tiles, to find them by their cells.
+        let mut entities = vec![None; cells * cells];
+        for (entity, _, position, _) in tiles.iter_mut() {
+            entities[position.index(cells)] = Some(entity);
+        }
 
-        // Iterate on the board according to the movement direction.
-        for curr_pos in moving_dir.board_iteration().iter() {
-            // Checking that a tile exists in the current position.
-            if let Some(curr_tile) = &board[curr_pos.index()] {
-                // Checking that the new position is not out of bounds.
-                if let Some(new_pos) = moving_dir.moved_position(curr_pos) {
-                    // Checking if the new position contains a tile.
-                    if let Some(existing_tile) = &board[new_pos.index()] {
-                        // If the existing tile is moving
-                        // or has the same level while both tiles are not merged,
-                        // move the current tile.
-                        if moving_entities.contains(&existing_tile.0)
-                            || (curr_tile.1.level == existing_tile.1.level
-                                && curr_tile.4.is_none()
-                                && existing_tile.4.is_none())
-                        {
-                            moving_entities.push(curr_tile.0);
-                        }
-                    } else {
-                        // If the new position is emtpy, move the current tile.
-                        moving_entities.push(curr_tile.0);
-                    }
+        // Each tile slides to its destination, the farther it goes the longer it takes.
+        // The tiles that arrive at each cell are saved in the order they have slid,
+        // so the merges can tell which tiles are merged.
+        let mut arrivals = vec![Vec::new(); cells * cells];
+        for slide in result.slides.iter() {
+            let from = Position {
+                row: slide.from.0,
+                col: slide.from.1,
+            };
+            let to = Position {
+                row: slide.to.0,
+                col: slide.to.1,
+            };
+            let entity = match entities[from.index(cells)] {
+                Some(entity) => entity,
+                None => continue,
+            };
+            arrivals[to.index(cells)].push(entity);
+
+            let distance = (from.row as isize - to.row as isize).abs()
+                + (from.col as isize - to.col as isize).abs();
+            if distance > 0 {
+                if let Ok(mut moving) = tiles.get_component_mut::<Option<Moving>>(entity) {
+                    *moving = Some(Moving {
+                        to,
+                        animation: Animation::new(
+                            SLIDE_SECONDS_PER_CELL * distance as f32,
+                            Easing::OutCubic,
+                        ),
+                    });
                 }
             }
         }
 
-        let moving = !moving_entities.is_empty();
+        // Each merge takes the next two tiles that arrived at its cell,
+        // the first one is nearer the edge, so the other is merged into it.
+        move_plan.merges = result
+            .merges
+            .iter()
+            .filter_map(|merge| {
+                let position = Position {
+                    row: merge.row,
+                    col: merge.col,
+                };
+                let arrived = &mut arrivals[position.index(cells)];
+                if arrived.len() < 2 {
+                    return None;
+                }
 
-        // Set the tiles that should move to `Moving`.
-        for entity in moving_entities {
-            commands.insert_one(entity, Some(Moving));
-        }
+                let mut pair = arrived.drain(..2);
+                Some(PlannedMerge {
+                    into: pair.next().unwrap(),
+                    from: pair.next().unwrap(),
+                    position,
+                    level: merge.level,
+                })
+            })
+            .collect();
+        move_plan.board = result.board;
 
-        *moving_state = if moving {
-            MovingState::Animating
-        } else {
-            MovingState::Finishing { moved: !starting }
-        };
+        *moving_state = MovingState::Animating;
     }
 }
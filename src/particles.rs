@@ -0,0 +1,314 @@
+//! This module contains the implementation of the particle effects.
+//! An `Emitter` entity emits particles for a while and is despawned when it's done.
+//! The particles are sprites that are kept in a pool when they die, so they are reused
+//! instead of being spawned again. Every length is measured in tiles, so the effects
+//! are scaled with the board.
+
+use bevy::{prelude::*, sprite::SpriteResizeMode};
+use bevy_2048::easing::Easing;
+use rand::Rng;
+
+use crate::common::{GameClock, GameSize, Settings};
+
+/// This plugin builds the particles into the app.
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<ParticlePool>()
+            .add_system(emit_particles.system())
+            .add_system(update_particles.system());
+    }
+}
+
+/// The most particles that can be alive at the same time.
+const MAX_PARTICLES: usize = 400;
+
+/// The particles are drawn over the tiles and their patterns.
+const PARTICLE_Z: f32 = 2.0;
+
+/// A value that changes over a particle's lifetime.
+#[derive(Debug, Copy, Clone)]
+pub struct Curve {
+    pub from: f32,
+    pub to: f32,
+    pub easing: Easing,
+}
+
+impl Curve {
+    pub fn new(from: f32, to: f32, easing: Easing) -> Self {
+        Self { from, to, easing }
+    }
+
+    /// Returns the value at `progress`, from 0 at the particle's birth to 1 at its death.
+    fn value(&self, progress: f32) -> f32 {
+        self.from + (self.to - self.from) * self.easing.apply(progress)
+    }
+}
+
+/// Returns a random value between the two ends of a range.
+fn random(rng: &mut impl Rng, (min, max): (f32, f32)) -> f32 {
+    min + (max - min) * rng.gen::<f32>()
+}
+
+/// Component of an entity that emits particles.
+#[derive(Debug, Clone)]
+pub struct Emitter {
+    /// Where the particles are emitted, in the world.
+    pub origin: Vec3,
+    /// The particles are emitted in a rectangle of this size around the origin.
+    pub area: Vec2,
+    /// How many particles are emitted.
+    pub count: usize,
+    /// The seconds the particles are emitted over, 0 emits them all at once.
+    pub seconds: f32,
+    /// The directions the particles are emitted in, as angles in radians.
+    pub angles: (f32, f32),
+    pub speed: (f32, f32),
+    /// How fast the particles fall.
+    pub gravity: f32,
+    pub lifetime: (f32, f32),
+    pub size: Curve,
+    /// Each particle has one of the colors, and its alpha is multiplied by the curve.
+    pub colors: Vec<Color>,
+    pub alpha: Curve,
+    elapsed: f32,
+    emitted: usize,
+}
+
+impl Emitter {
+    /// A small burst of particles that fly out of a merged tile.
+    pub fn merge_burst(origin: Vec3, color: Color) -> Self {
+        Self {
+            origin,
+            area: Vec2::new(0.6, 0.6),
+            count: 12,
+            seconds: 0.0,
+            angles: (0.0, std::f32::consts::TAU),
+            speed: (1.0, 2.5),
+            gravity: 0.0,
+            lifetime: (0.25, 0.45),
+            size: Curve::new(0.12, 0.02, Easing::InCubic),
+            colors: vec![color],
+            alpha: Curve::new(1.0, 0.0, Easing::InCubic),
+            elapsed: 0.0,
+            emitted: 0,
+        }
+    }
+
+    /// A bigger burst for a tile that is higher than any other tile of the game.
+    pub fn celebration(origin: Vec3, color: Color) -> Self {
+        Self {
+            count: 40,
+            seconds: 0.2,
+            speed: (2.0, 4.5),
+            gravity: 3.0,
+            lifetime: (0.5, 0.9),
+            size: Curve::new(0.18, 0.04, Easing::InCubic),
+            colors: vec![color, Color::WHITE],
+            ..Self::merge_burst(origin, color)
+        }
+    }
+
+    /// Confetti that falls over the whole board.
+    pub fn confetti(game_size: &GameSize, colors: Vec<Color>) -> Self {
+        let board_tiles = game_size.board_size() / game_size.tile_size();
+        Self {
+            origin: Vec3::new(0.0, game_size.board_size() / 2.0, 0.0),
+            area: Vec2::new(board_tiles, 0.2),
+            count: 150,
+            seconds: 1.5,
+            angles: (-2.0, -1.1),
+            speed: (0.5, 1.5),
+            gravity: 2.0,
+            lifetime: (1.5, 2.5),
+            size: Curve::new(0.12, 0.1, Easing::Linear),
+            colors,
+            alpha: Curve::new(1.0, 0.0, Easing::InCubic),
+            elapsed: 0.0,
+            emitted: 0,
+        }
+    }
+
+    /// Returns how many particles should have been emitted by now.
+    fn due(&self) -> usize {
+        if self.elapsed >= self.seconds {
+            self.count
+        } else {
+            (self.count as f32 * self.elapsed / self.seconds) as usize
+        }
+    }
+}
+
+/// Component of a particle.
+pub struct Particle {
+    /// The particle's velocity, in pixels per second.
+    velocity: Vec2,
+    /// The particle's gravity, in pixels per second squared.
+    gravity: f32,
+    age: f32,
+    lifetime: f32,
+    /// The particle's size curve, in pixels.
+    size: Curve,
+    color: Color,
+    alpha: Curve,
+}
+
+impl Particle {
+    /// Returns `true` if the particle has died.
+    fn dead(&self) -> bool {
+        self.age >= self.lifetime
+    }
+}
+
+/// This struct saves the particles that have died, so they can be reused.
+#[derive(Debug, Default)]
+pub struct ParticlePool {
+    free: Vec<Entity>,
+    /// How many particles have been spawned, alive or not.
+    spawned: usize,
+}
+
+/// This system emits the particles of the emitters, and despawns the emitters that are done.
+/// When the particles are disabled in the settings, the emitters are despawned right away.
+#[allow(clippy::type_complexity)]
+fn emit_particles(
+    mut commands: Commands,
+    (clock, game_size, settings): (Res<GameClock>, Res<GameSize>, Res<Settings>),
+    mut pool: ResMut<ParticlePool>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut emitters: Query<(Entity, &mut Emitter)>,
+    mut particles: Query<(
+        &mut Particle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Draw,
+        &Handle<ColorMaterial>,
+    )>,
+) {
+    let mut rng = rand::thread_rng();
+    let tile_size = game_size.tile_size();
+
+    for (entity, mut emitter) in emitters.iter_mut() {
+        if !settings.particles {
+            commands.despawn(entity);
+            continue;
+        }
+
+        emitter.elapsed += clock.delta_seconds;
+        let due = emitter.due();
+
+        while emitter.emitted < due {
+            emitter.emitted += 1;
+
+            let angle = random(&mut rng, emitter.angles);
+            let offset = Vec2::new(
+                random(&mut rng, (-0.5, 0.5)) * emitter.area.x(),
+                random(&mut rng, (-0.5, 0.5)) * emitter.area.y(),
+            ) * tile_size;
+            let color = emitter.colors[rng.gen_range(0, emitter.colors.len())];
+            let particle = Particle {
+                velocity: Vec2::new(angle.cos(), angle.sin())
+                    * random(&mut rng, emitter.speed)
+                    * tile_size,
+                gravity: emitter.gravity * tile_size,
+                age: 0.0,
+                lifetime: random(&mut rng, emitter.lifetime),
+                size: Curve::new(
+                    emitter.size.from * tile_size,
+                    emitter.size.to * tile_size,
+                    emitter.size.easing,
+                ),
+                color,
+                alpha: emitter.alpha,
+            };
+            let translation = emitter.origin + offset.extend(0.0) + Vec3::unit_z() * PARTICLE_Z;
+            let size = Vec2::new(particle.size.from, particle.size.from);
+
+            // A dead particle is reused, a new one is only spawned when there is none.
+            if let Some(free) = pool.free.pop() {
+                if let Ok((mut old, mut transform, mut sprite, mut draw, handle)) =
+                    particles.get_mut(free)
+                {
+                    if let Some(material) = materials.get_mut(handle) {
+                        material.color = color;
+                    }
+                    *old = particle;
+                    transform.translation = translation;
+                    sprite.size = size;
+                    draw.is_visible = true;
+                }
+            } else if pool.spawned < MAX_PARTICLES {
+                pool.spawned += 1;
+                commands
+                    .spawn(SpriteComponents {
+                        material: materials.add(color.into()),
+                        sprite: Sprite {
+                            size,
+                            resize_mode: SpriteResizeMode::Manual,
+                        },
+                        transform: Transform::from_translation(translation),
+                        ..Default::default()
+                    })
+                    .with(particle);
+            }
+        }
+
+        if emitter.emitted >= emitter.count {
+            commands.despawn(entity);
+        }
+    }
+}
+
+/// This system moves the particles and changes their sizes and colors along their curves.
+/// The particles that die are hidden and put into the pool.
+#[allow(clippy::type_complexity)]
+fn update_particles(
+    clock: Res<GameClock>,
+    settings: Res<Settings>,
+    mut pool: ResMut<ParticlePool>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut particles: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Draw,
+        &Handle<ColorMaterial>,
+    )>,
+) {
+    let delta = clock.delta_seconds;
+    if delta == 0.0 && settings.particles {
+        return;
+    }
+
+    for (entity, mut particle, mut transform, mut sprite, mut draw, handle) in particles.iter_mut()
+    {
+        if particle.dead() {
+            continue;
+        }
+
+        particle.age += delta;
+        // The particles that are alive die when the particles are disabled.
+        if particle.dead() || !settings.particles {
+            particle.age = particle.lifetime;
+            draw.is_visible = false;
+            pool.free.push(entity);
+            continue;
+        }
+
+        let fall = Vec2::new(0.0, particle.gravity * delta);
+        particle.velocity -= fall;
+        transform.translation += particle.velocity.extend(0.0) * delta;
+
+        let progress = particle.age / particle.lifetime;
+        let size = particle.size.value(progress);
+        sprite.size = Vec2::new(size, size);
+
+        if let Some(material) = materials.get_mut(handle) {
+            let mut color = particle.color;
+            color.set_a(particle.color.a() * particle.alpha.value(progress));
+            material.color = color;
+        }
+    }
+}
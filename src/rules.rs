@@ -0,0 +1,463 @@
+//! This module contains a headless implementation of the game's rules.
+//! The board systems and the reinforcement-learning environment
+//! are both built on it, so the rules won't drift apart.
+
+use rand::{Rng, RngCore};
+
+/// The last level a tile can have.
+/// Merging two tiles of this level removes both of them.
+pub const MAX_LEVEL: u32 = 9;
+
+/// The highest level any ruleset can give a tile, the score of a higher tile
+/// wouldn't fit into a `u32`.
+pub const MAX_RULES_LEVEL: u32 = 30;
+
+/// The size of the board in the classic game.
+pub const DEFAULT_SIZE: usize = 4;
+
+/// The direction of a move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Up,
+    Right,
+    Down,
+}
+
+/// All the directions, ordered by their index.
+pub const DIRECTIONS: [Direction; 4] = [
+    Direction::Left,
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+];
+
+impl Direction {
+    /// Returns the index of the direction in `DIRECTIONS`.
+    pub fn index(self) -> usize {
+        match self {
+            Self::Left => 0,
+            Self::Up => 1,
+            Self::Right => 2,
+            Self::Down => 3,
+        }
+    }
+
+    /// Returns the direction with the given index in `DIRECTIONS`.
+    pub fn from_index(index: usize) -> Option<Self> {
+        DIRECTIONS.get(index).copied()
+    }
+}
+
+/// Calculates the score of a tile with a given level (pow(2, level + 1)).
+pub fn tile_score(level: u32) -> u32 {
+    2u32.pow(level + 1)
+}
+
+/// Chooses the level of a new tile.
+pub fn spawn_level<R: Rng + ?Sized>(rng: &mut R) -> u32 {
+    if rng.gen_bool(0.8) {
+        0
+    } else {
+        1
+    }
+}
+
+/// The rules that can be changed by a game mode.
+/// The provided methods are the rules of the classic game.
+pub trait Ruleset {
+    /// Chooses the cell and the level of a new tile as `(row, col, level)`.
+    /// Returns `None` if the board is full.
+    fn spawn(&self, board: &Board, rng: &mut dyn RngCore) -> Option<(usize, usize, u32)> {
+        let empty = board.empty_cells();
+        if empty.is_empty() {
+            return None;
+        }
+
+        let (row, col) = empty[rng.gen_range(0, empty.len())];
+        Some((row, col, spawn_level(rng)))
+    }
+
+    /// Checks if a tile can be merged into a tile with the level `into`.
+    fn can_merge(&self, into: u32, level: u32) -> bool {
+        into == level
+    }
+
+    /// Returns the level of the merged tile, or `None` if it should be removed.
+    fn merge_result(&self, into: u32, _level: u32) -> Option<u32> {
+        if into < MAX_LEVEL {
+            Some(into + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the score of the tile that wins the game, 2048 in the classic game.
+    fn win_score(&self) -> u32 {
+        tile_score(MAX_LEVEL + 1)
+    }
+
+    /// Checks if merging a tile with `level` into a tile with `into` reaches the winning tile.
+    /// A merge that removes the tiles wins if the tile it would have made reaches it.
+    fn is_winning_merge(&self, into: u32, level: u32, merged: Option<u32>) -> bool {
+        let score = merged.map_or_else(
+            || tile_score(into).saturating_add(tile_score(level)),
+            tile_score,
+        );
+        score >= self.win_score()
+    }
+
+    /// Called after every move that changed the board.
+    /// Returns a score bonus.
+    fn on_move_finished(&self, _board: &Board, _score: u32) -> u32 {
+        0
+    }
+
+    /// Checks if there are no moves left.
+    fn is_game_over(&self, board: &Board) -> bool {
+        DIRECTIONS
+            .iter()
+            .all(|&direction| !board.slide_with(direction, self).moved)
+    }
+}
+
+/// The rules of the classic game.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Classic;
+
+impl Ruleset for Classic {
+    fn is_game_over(&self, board: &Board) -> bool {
+        board.is_game_over()
+    }
+}
+
+/// A merge that happened during a move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Merge {
+    pub row: usize,
+    pub col: usize,
+    /// The level of the merged tile, `None` if it has been removed from the board.
+    pub level: Option<u32>,
+}
+
+/// Where a tile has slid to during a move.
+/// A tile that is merged slides to the cell of the tile it's merged into.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Slide {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+}
+
+/// The result of sliding the board in a direction.
+#[derive(Debug, Clone)]
+pub struct MoveResult {
+    pub board: Board,
+    /// Tells if any tile has been moved or merged.
+    pub moved: bool,
+    /// Every tile of the board before the move, with the cell it has slid to.
+    pub slides: Vec<Slide>,
+    pub merges: Vec<Merge>,
+    /// The score gained by the merges.
+    pub score: u32,
+}
+
+/// A square board of tile levels.
+/// Rows are counted from the bottom and columns from the left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    size: usize,
+    cells: Vec<Option<u32>>,
+}
+
+impl Board {
+    /// Creates an empty board.
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            cells: vec![None; size * size],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the level of the tile at a cell.
+    pub fn get(&self, row: usize, col: usize) -> Option<u32> {
+        self.cells[row * self.size + col]
+    }
+
+    /// Sets the level of the tile at a cell.
+    pub fn set(&mut self, row: usize, col: usize, level: Option<u32>) {
+        self.cells[row * self.size + col] = level;
+    }
+
+    /// Returns the levels of all cells, row after row.
+    pub fn cells(&self) -> &[Option<u32>] {
+        &self.cells
+    }
+
+    /// Returns the empty cells as `(row, col)`.
+    pub fn empty_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.cells.len())
+            .filter(|&index| self.cells[index].is_none())
+            .map(|index| (index / self.size, index % self.size))
+            .collect()
+    }
+
+    /// Returns the highest level on the board.
+    pub fn max_level(&self) -> Option<u32> {
+        self.cells.iter().flatten().copied().max()
+    }
+
+    /// Spawns a tile on a random empty cell.
+    /// Returns the new tile as `(row, col, level)`, or `None` if the board is full.
+    pub fn spawn_random(&mut self, rng: &mut impl Rng) -> Option<(usize, usize, u32)> {
+        let spawned = Classic.spawn(self, rng);
+        if let Some((row, col, level)) = spawned {
+            self.set(row, col, Some(level));
+        }
+
+        spawned
+    }
+
+    /// Returns the cells of each line in a direction, starting
+    /// from the edge the tiles are moving towards.
+    fn lines(&self, direction: Direction) -> Vec<Vec<(usize, usize)>> {
+        let last = self.size - 1;
+        (0..self.size)
+            .map(|secondary| {
+                (0..self.size)
+                    .map(|primary| match direction {
+                        Direction::Left => (secondary, primary),
+                        Direction::Right => (secondary, last - primary),
+                        Direction::Down => (primary, secondary),
+                        Direction::Up => (last - primary, secondary),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Slides all the tiles in a direction.
+    /// Each tile can be merged once per move, and two tiles
+    /// with the last level are removed when merged.
+    pub fn slide(&self, direction: Direction) -> MoveResult {
+        self.slide_with(direction, &Classic)
+    }
+
+    /// Slides all the tiles in a direction, merging them by the given rules.
+    /// Each tile can be merged once per move.
+    pub fn slide_with<R: Ruleset + ?Sized>(&self, direction: Direction, rules: &R) -> MoveResult {
+        let mut board = Board::new(self.size);
+        let mut slides = Vec::new();
+        let mut merges = Vec::new();
+        let mut score: u32 = 0;
+
+        for line in self.lines(direction) {
+            // The tiles that were placed in this line, and whether they were merged.
+            let mut placed: Vec<(u32, bool)> = Vec::new();
+
+            for &(row, col) in &line {
+                if let Some(level) = self.get(row, col) {
+                    let into = match placed.last() {
+                        Some(&(last, false)) if rules.can_merge(last, level) => Some(last),
+                        _ => None,
+                    };
+
+                    if let Some(into) = into {
+                        let (merge_row, merge_col) = line[placed.len() - 1];
+                        slides.push(Slide {
+                            from: (row, col),
+                            to: (merge_row, merge_col),
+                        });
+                        let merged_level = rules.merge_result(into, level);
+                        merges.push(Merge {
+                            row: merge_row,
+                            col: merge_col,
+                            level: merged_level,
+                        });
+
+                        if let Some(merged_level) = merged_level {
+                            *placed.last_mut().unwrap() = (merged_level, true);
+                            score = score.saturating_add(tile_score(merged_level));
+                        } else {
+                            placed.pop();
+                        }
+                    } else {
+                        slides.push(Slide {
+                            from: (row, col),
+                            to: line[placed.len()],
+                        });
+                        placed.push((level, false));
+                    }
+                }
+            }
+
+            for (&(row, col), &(level, _)) in line.iter().zip(placed.iter()) {
+                board.set(row, col, Some(level));
+            }
+        }
+
+        MoveResult {
+            moved: board != *self,
+            board,
+            slides,
+            merges,
+            score,
+        }
+    }
+
+    /// Checks if any tile can move in a direction.
+    pub fn can_move(&self, direction: Direction) -> bool {
+        self.slide(direction).moved
+    }
+
+    /// Returns the directions that move at least one tile.
+    pub fn legal_moves(&self) -> Vec<Direction> {
+        self.legal_moves_with(&Classic)
+    }
+
+    /// Returns the directions that move at least one tile by the given rules.
+    pub fn legal_moves_with<R: Ruleset + ?Sized>(&self, rules: &R) -> Vec<Direction> {
+        DIRECTIONS
+            .iter()
+            .copied()
+            .filter(|&direction| self.slide_with(direction, rules).moved)
+            .collect()
+    }
+
+    /// Checks if there are no moves left by the classic rules.
+    pub fn is_game_over(&self) -> bool {
+        // A board with an empty cell always has a move.
+        if self.cells.iter().any(Option::is_none) {
+            return false;
+        }
+
+        // Checking if there are some neighbor tiles with the same level.
+        for row in 0..self.size {
+            for col in 0..self.size {
+                let level = self.get(row, col);
+                if (row + 1 < self.size && self.get(row + 1, col) == level)
+                    || (col + 1 < self.size && self.get(row, col + 1) == level)
+                {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a board from its rows, from the bottom row, where -1 is an empty cell.
+    fn board(rows: &[&[i32]]) -> Board {
+        let mut board = Board::new(rows.len());
+        for (row, levels) in rows.iter().enumerate() {
+            for (col, &level) in levels.iter().enumerate() {
+                if level >= 0 {
+                    board.set(row, col, Some(level as u32));
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn tiles_merge_once_per_move() {
+        let result =
+            board(&[&[1, 1, 1, 1], &[0, 0, 1, -1], &[-1; 4], &[-1; 4]]).slide(Direction::Left);
+
+        assert!(result.moved);
+        assert_eq!(
+            result.board,
+            board(&[&[2, 2, -1, -1], &[1, 1, -1, -1], &[-1; 4], &[-1; 4]])
+        );
+        assert_eq!(result.merges.len(), 3);
+        assert_eq!(result.score, 2 * tile_score(2) + tile_score(1));
+    }
+
+    #[test]
+    fn tiles_slide_towards_the_direction() {
+        let result = board(&[&[0, -1, -1, -1], &[-1; 4], &[-1; 4], &[-1; 4]]).slide(Direction::Up);
+
+        assert_eq!(
+            result.board,
+            board(&[&[-1; 4], &[-1; 4], &[-1; 4], &[0, -1, -1, -1]])
+        );
+        assert_eq!(
+            result.slides,
+            vec![Slide {
+                from: (0, 0),
+                to: (3, 0),
+            }]
+        );
+    }
+
+    #[test]
+    fn last_level_pair_is_removed() {
+        let max = MAX_LEVEL as i32;
+        let result =
+            board(&[&[max, max, 0, -1], &[-1; 4], &[-1; 4], &[-1; 4]]).slide(Direction::Left);
+
+        assert!(result.moved);
+        assert_eq!(
+            result.board,
+            board(&[&[0, -1, -1, -1], &[-1; 4], &[-1; 4], &[-1; 4]])
+        );
+        assert_eq!(
+            result.merges,
+            vec![Merge {
+                row: 0,
+                col: 0,
+                level: None,
+            }]
+        );
+        assert_eq!(result.score, 0);
+    }
+
+    #[test]
+    fn move_that_changes_nothing() {
+        let start = board(&[&[0, 1, -1, -1], &[2, -1, -1, -1], &[-1; 4], &[-1; 4]]);
+        let result = start.slide(Direction::Left);
+
+        assert!(!result.moved);
+        assert_eq!(result.board, start);
+        assert!(result.merges.is_empty());
+        assert_eq!(result.score, 0);
+    }
+
+    #[test]
+    fn legal_moves_of_a_corner_tile() {
+        let start = board(&[&[0, -1, -1], &[-1; 3], &[-1; 3]]);
+
+        assert_eq!(start.legal_moves(), vec![Direction::Up, Direction::Right]);
+        assert!(start.can_move(Direction::Right));
+        assert!(!start.can_move(Direction::Left));
+    }
+
+    #[test]
+    fn game_over_needs_a_full_board_without_pairs() {
+        let stuck = board(&[&[0, 1, 0], &[1, 0, 1], &[0, 1, 0]]);
+        assert!(stuck.is_game_over());
+        assert!(stuck.legal_moves().is_empty());
+        assert!(Classic.is_game_over(&stuck));
+
+        let pair = board(&[&[0, 0, 1], &[1, 2, 0], &[0, 1, 2]]);
+        assert!(!pair.is_game_over());
+
+        let empty_cell = board(&[&[0, 1, 0], &[1, -1, 1], &[0, 1, 0]]);
+        assert!(!empty_cell.is_game_over());
+    }
+
+    #[test]
+    fn removal_merge_of_the_last_level_wins() {
+        assert!(Classic.is_winning_merge(MAX_LEVEL, MAX_LEVEL, None));
+        assert!(!Classic.is_winning_merge(MAX_LEVEL - 1, MAX_LEVEL - 1, Some(MAX_LEVEL)));
+        assert!(Classic.is_winning_merge(MAX_LEVEL, MAX_LEVEL, Some(MAX_LEVEL + 1)));
+    }
+}
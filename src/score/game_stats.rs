@@ -0,0 +1,57 @@
+//! This module contains the implementation of the GameStats resource.
+
+use bevy::prelude::*;
+
+use super::HighScore;
+use crate::common::GameEvent;
+
+/// This struct saves statistics of the current game.
+#[derive(Debug)]
+pub struct GameStats {
+    /// The number of moves that changed the board.
+    pub moves: u32,
+    /// The highscore before the game has started.
+    pub previous_highscore: u32,
+}
+
+impl FromResources for GameStats {
+    fn from_resources(resources: &Resources) -> Self {
+        let highscore = resources.get::<HighScore>().unwrap();
+        Self {
+            moves: 0,
+            previous_highscore: highscore.0,
+        }
+    }
+}
+
+impl GameStats {
+    /// Checks if a score beats the highscore from before the game.
+    pub fn is_new_highscore(&self, score: u32) -> bool {
+        score > self.previous_highscore
+    }
+}
+
+/// Event listener for GameEvent.
+#[derive(Default)]
+pub struct GameStatsListener {
+    pub reader: EventReader<GameEvent>,
+}
+
+/// This system counts the moves and resets the statistics on a new game.
+pub fn update_game_stats(
+    mut listener: ResMut<GameStatsListener>,
+    game_events: Res<Events<GameEvent>>,
+    highscore: Res<HighScore>,
+    mut stats: ResMut<GameStats>,
+) {
+    for event in listener.reader.iter(&game_events) {
+        match event {
+            GameEvent::Slid { .. } => stats.moves += 1,
+            GameEvent::NewGame => {
+                stats.moves = 0;
+                stats.previous_highscore = highscore.0;
+            }
+            _ => {}
+        }
+    }
+}
@@ -2,9 +2,9 @@
 
 use bevy::prelude::*;
 use savefile::prelude::*;
-use std::{env, error::Error, fs};
 
 use super::Score;
+use crate::common::fulldir;
 
 /// This struct manages the highscore and saves it into a binary file `best.bin`.
 #[derive(Savefile)]
@@ -42,29 +42,6 @@ pub fn update_highscore(mut highscore: ResMut<HighScore>, score: Res<Score>) {
     }
 }
 
-/// Gets `filename`, returns the full path `{path_to_exe}/data/{filename}`.
-/// If `create_dir` is true, then the directory `data` is being created.
-fn fulldir(filename: &str, create_dir: bool) -> Result<String, Box<dyn Error>> {
-    let mut path = env::current_exe()?
-        .parent() // removes the exe name from the path.
-        .ok_or("Couldn't get parent")?
-        .to_path_buf();
-
-    path.push("data");
-
-    if create_dir {
-        fs::create_dir_all(&path)?;
-    }
-
-    path.push(filename);
-
-    // Transforms the path into String.
-    Ok(path
-        .to_str()
-        .ok_or("Couldn't parse os_str to str")?
-        .to_owned())
-}
-
 /// Prints an error message into the console.
 /// If in debug mode then it prints with more verbose.
 fn print_error<T: std::fmt::Display>(e: T, err_type: ErrorType) {
@@ -2,8 +2,12 @@
 
 use bevy::prelude::*;
 
+mod game_stats;
 mod highscore;
+mod statistics;
+pub use game_stats::GameStats;
 pub use highscore::HighScore;
+pub use statistics::Statistics;
 
 /// This struct saves the score of the current game.
 pub struct Score(pub u32);
@@ -15,6 +19,12 @@ impl Plugin for ScoreSystemPlugin {
     fn build(&self, app: &mut bevy::prelude::AppBuilder) {
         app.init_resource::<HighScore>()
             .add_resource(Score(0))
-            .add_system(highscore::update_highscore.system());
+            .init_resource::<GameStats>()
+            .init_resource::<game_stats::GameStatsListener>()
+            .add_resource(Statistics::load())
+            .init_resource::<statistics::StatisticsListener>()
+            .add_system(highscore::update_highscore.system())
+            .add_system(game_stats::update_game_stats.system())
+            .add_system(statistics::update_statistics.system());
     }
 }
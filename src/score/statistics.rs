@@ -0,0 +1,106 @@
+//! This module contains the implementation of the Statistics resource,
+//! which sums up all the games that have been played.
+
+use bevy::prelude::*;
+use bevy_2048::rules;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs};
+
+use crate::{
+    common::{fulldir, GameEvent},
+    session::SessionEvent,
+};
+
+/// The file in the data directory the statistics are saved into.
+const STATISTICS_FILE: &str = "statistics.json";
+
+/// This struct saves the statistics of all the games,
+/// and saves them into `statistics.json` in the data directory.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    /// The number of games that have been started.
+    pub games_played: u32,
+    /// The number of games that have ended with no more moves.
+    pub games_over: u32,
+    /// The number of moves that changed the board.
+    pub moves: u32,
+    /// The score of the highest tile.
+    pub best_tile: u32,
+}
+
+impl Statistics {
+    /// Trys to load the statistics from the file.
+    /// If it fails the statistics start from zero.
+    pub fn load() -> Self {
+        let result: Result<Self, Box<dyn Error>> = fulldir(STATISTICS_FILE, false)
+            .and_then(|filepath| Ok(serde_json::from_str(&fs::read_to_string(filepath)?)?));
+
+        result.unwrap_or_else(|e| {
+            eprintln!("Couldn't load the statistics: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Saves the statistics into the file.
+    /// Errors are printed into the console.
+    fn save(&self) {
+        let result = fulldir(STATISTICS_FILE, true).and_then(|filepath| {
+            fs::write(filepath, serde_json::to_string_pretty(self)?)?;
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            eprintln!("Couldn't save the statistics: {}", e);
+        }
+    }
+}
+
+/// Event listener for the events that change the statistics.
+#[derive(Default)]
+pub struct StatisticsListener {
+    pub game_reader: EventReader<GameEvent>,
+    pub session_reader: EventReader<SessionEvent>,
+}
+
+/// This system updates the statistics from the game events.
+/// They are saved when a game starts, ends or is left.
+pub fn update_statistics(
+    mut listener: ResMut<StatisticsListener>,
+    game_events: Res<Events<GameEvent>>,
+    session_events: Res<Events<SessionEvent>>,
+    mut statistics: ResMut<Statistics>,
+) {
+    let mut changed = false;
+
+    for event in listener.game_reader.iter(&game_events) {
+        match *event {
+            GameEvent::NewGame => {
+                statistics.games_played += 1;
+                changed = true;
+            }
+            GameEvent::GameOver => {
+                statistics.games_over += 1;
+                changed = true;
+            }
+            GameEvent::Slid { .. } => statistics.moves += 1,
+            GameEvent::Spawned { level, .. }
+            | GameEvent::Merged {
+                level: Some(level), ..
+            } => {
+                statistics.best_tile = statistics.best_tile.max(rules::tile_score(level));
+            }
+            _ => {}
+        }
+    }
+
+    for event in listener.session_reader.iter(&session_events) {
+        if matches!(event, SessionEvent::Leave) {
+            changed = true;
+        }
+    }
+
+    if changed {
+        statistics.save();
+    }
+}
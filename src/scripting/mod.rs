@@ -0,0 +1,88 @@
+//! This module contains the game modes written as Rhai scripts.
+//! A mode is loaded from `assets/modes/<name>.rhai` and can define the hooks
+//! `on_spawn`, `can_merge`, `merge_result`, `on_move_finished` and `is_game_over`.
+//! Hooks that aren't defined keep the rules of the classic game.
+
+mod script_rules;
+
+use bevy::prelude::*;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    common::{asset_names, GameRules},
+    ui::ErrorEvent,
+};
+use script_rules::ScriptRules;
+
+/// The errors of the script, shared with the rules that run it.
+#[derive(Default, Clone)]
+pub struct ScriptErrors(Arc<Mutex<Vec<String>>>);
+
+impl ScriptErrors {
+    /// Saves an error until it is reported.
+    pub fn push(&self, message: String) {
+        self.0.lock().unwrap().push(message);
+    }
+}
+
+/// The name of the mode that uses the built-in classic rules.
+pub const CLASSIC_MODE: &str = "classic";
+
+/// The name of the current mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameMode(pub String);
+
+impl Default for GameMode {
+    fn default() -> Self {
+        Self(CLASSIC_MODE.to_string())
+    }
+}
+
+/// This plugin loads the mode the game has been launched with,
+/// and replaces the game's rules with it.
+/// Without a mode the game starts with the classic rules.
+pub struct ScriptingPlugin(pub Option<String>);
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        let errors = ScriptErrors::default();
+
+        if let Some(name) = &self.0 {
+            app.add_resource(load_rules(name, &errors))
+                .add_resource(GameMode(name.clone()));
+        } else {
+            app.init_resource::<GameMode>();
+        }
+
+        app.add_resource(errors)
+            .add_system(report_script_errors.system());
+    }
+}
+
+/// Loads the rules of a mode.
+/// When the mode can't be loaded, the classic rules are used and the error is saved in `errors`.
+pub fn load_rules(name: &str, errors: &ScriptErrors) -> GameRules {
+    if name == CLASSIC_MODE {
+        return GameRules::default();
+    }
+
+    match ScriptRules::load(name, errors.clone()) {
+        Some(rules) => GameRules(Box::new(rules)),
+        None => GameRules::default(),
+    }
+}
+
+/// Lists the modes in `assets/modes`, the classic mode comes first.
+pub fn available_modes() -> Vec<String> {
+    let mut modes = asset_names("modes", "rhai");
+    modes.retain(|name| name != CLASSIC_MODE);
+    modes.insert(0, CLASSIC_MODE.to_string());
+    modes
+}
+
+/// Reports the errors of the script in the error panel.
+fn report_script_errors(errors: Res<ScriptErrors>, mut error_events: ResMut<Events<ErrorEvent>>) {
+    for message in errors.0.lock().unwrap().drain(..) {
+        error_events.send(ErrorEvent { message });
+    }
+}
@@ -0,0 +1,316 @@
+//! This module contains the implementation of the rules that run a script.
+
+use bevy_2048::rules::{Board, Classic, Ruleset, MAX_RULES_LEVEL};
+use rand::{Rng, RngCore};
+use rhai::{Array, Dynamic, Engine, FuncArgs, Map, Scope, AST, INT};
+use std::{collections::HashSet, fs};
+
+use super::ScriptErrors;
+use crate::common::asset_path;
+
+/// Limits the number of operations of a single hook, so an endless loop won't freeze the game.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Limits the nesting of expressions, at the top level and inside functions.
+/// The defaults of debug builds are too low for the nested loops of a hook.
+const MAX_EXPR_DEPTHS: (usize, usize) = (64, 64);
+
+/// Rules that call the hooks of a script.
+/// When a hook isn't defined or fails, the classic rule is used instead.
+pub struct ScriptRules {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    hooks: HashSet<String>,
+    errors: ScriptErrors,
+}
+
+impl ScriptRules {
+    /// Loads the mode from `assets/modes/<name>.rhai`.
+    /// Returns `None` if the script couldn't be loaded, the error is saved in `errors`.
+    pub fn load(name: &str, errors: ScriptErrors) -> Option<Self> {
+        let path = asset_path("modes", &format!("{}.rhai", name));
+
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                errors.push(format!("Couldn't load mode `{}`: {}", name, e));
+                return None;
+            }
+        };
+
+        Self::compile(name, &source, errors)
+    }
+
+    /// Compiles the script of a mode.
+    /// Returns `None` if the script couldn't be compiled, the error is saved in `errors`.
+    pub fn compile(name: &str, source: &str, errors: ScriptErrors) -> Option<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_expr_depths(MAX_EXPR_DEPTHS.0, MAX_EXPR_DEPTHS.1);
+        // Scripts can't use the game's random generator, so they get their own.
+        engine.register_fn("random", |max: INT| {
+            if max > 0 {
+                rand::thread_rng().gen_range(0, max)
+            } else {
+                0
+            }
+        });
+
+        let ast = match engine.compile(source) {
+            Ok(ast) => ast,
+            Err(e) => {
+                errors.push(format!("Couldn't compile mode `{}`: {}", name, e));
+                return None;
+            }
+        };
+
+        let hooks = ast.iter_functions().map(|f| f.name.to_string()).collect();
+
+        Some(Self {
+            name: name.to_string(),
+            engine,
+            ast,
+            hooks,
+            errors,
+        })
+    }
+
+    /// Calls a hook of the script.
+    /// Returns `None` if the hook isn't defined or if it failed.
+    fn call(&self, hook: &str, args: impl FuncArgs) -> Option<Dynamic> {
+        if !self.hooks.contains(hook) {
+            return None;
+        }
+
+        match self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, hook, args)
+        {
+            Ok(result) => Some(result),
+            Err(e) => {
+                self.report(hook, e.to_string());
+                None
+            }
+        }
+    }
+
+    /// Saves an error of a hook.
+    fn report(&self, hook: &str, message: String) {
+        self.errors
+            .push(format!("{}.rhai: {}(): {}", self.name, hook, message));
+    }
+
+    /// Converts a result of a hook into a type, reporting a wrong type.
+    fn cast<T: Clone + 'static>(&self, hook: &str, result: Dynamic) -> Option<T> {
+        let type_name = result.type_name();
+        let value = result.try_cast::<T>();
+        if value.is_none() {
+            self.report(hook, format!("Unexpected return type `{}`", type_name));
+        }
+
+        value
+    }
+
+    /// Converts a level returned by a hook, reporting a level that is negative
+    /// or too high for its score to be counted.
+    fn to_level(&self, hook: &str, value: INT) -> Option<u32> {
+        if (0..=MAX_RULES_LEVEL as INT).contains(&value) {
+            Some(value as u32)
+        } else {
+            self.report(
+                hook,
+                format!("Level {} is out of 0..={}", value, MAX_RULES_LEVEL),
+            );
+            None
+        }
+    }
+}
+
+/// Converts the board into an array of rows, from the bottom row.
+/// Empty cells are -1.
+fn board_to_array(board: &Board) -> Array {
+    (0..board.size())
+        .map(|row| {
+            let row: Array = (0..board.size())
+                .map(|col| Dynamic::from(board.get(row, col).map_or(-1, |level| level as INT)))
+                .collect();
+            Dynamic::from(row)
+        })
+        .collect()
+}
+
+impl Ruleset for ScriptRules {
+    /// `on_spawn(board)` returns `#{ row: .., col: .., level: .. }`,
+    /// or `()` for a random tile.
+    fn spawn(&self, board: &Board, rng: &mut dyn RngCore) -> Option<(usize, usize, u32)> {
+        const HOOK: &str = "on_spawn";
+
+        if let Some(result) = self.call(HOOK, (board_to_array(board),)) {
+            if !result.is_unit() {
+                let spawned = self.cast::<Map>(HOOK, result).and_then(|map| {
+                    let get = |key: &str| map.get(key).and_then(|value| value.as_int().ok());
+                    Some((
+                        get("row")? as usize,
+                        get("col")? as usize,
+                        self.to_level(HOOK, get("level")?)?,
+                    ))
+                });
+
+                match spawned {
+                    Some((row, col, level))
+                        if row < board.size()
+                            && col < board.size()
+                            && board.get(row, col).is_none() =>
+                    {
+                        return Some((row, col, level))
+                    }
+                    _ => self.report(HOOK, "Expected an empty cell and a level".to_string()),
+                }
+            }
+        }
+
+        Classic.spawn(board, rng)
+    }
+
+    /// `can_merge(into, level)` returns a bool.
+    fn can_merge(&self, into: u32, level: u32) -> bool {
+        const HOOK: &str = "can_merge";
+
+        self.call(HOOK, (into as INT, level as INT))
+            .and_then(|result| self.cast::<bool>(HOOK, result))
+            .unwrap_or_else(|| Classic.can_merge(into, level))
+    }
+
+    /// `merge_result(into, level)` returns the merged level,
+    /// or `()` if the merged tile should be removed.
+    fn merge_result(&self, into: u32, level: u32) -> Option<u32> {
+        const HOOK: &str = "merge_result";
+
+        match self.call(HOOK, (into as INT, level as INT)) {
+            Some(result) if result.is_unit() => None,
+            Some(result) => self
+                .cast::<INT>(HOOK, result)
+                .and_then(|merged| self.to_level(HOOK, merged))
+                .or_else(|| Classic.merge_result(into, level)),
+            None => Classic.merge_result(into, level),
+        }
+    }
+
+    /// `on_move_finished(board, score)` returns a score bonus or `()`.
+    fn on_move_finished(&self, board: &Board, score: u32) -> u32 {
+        const HOOK: &str = "on_move_finished";
+
+        match self.call(HOOK, (board_to_array(board), score as INT)) {
+            Some(result) if !result.is_unit() => self
+                .cast::<INT>(HOOK, result)
+                .map_or(0, |bonus| bonus.clamp(0, u32::MAX as INT) as u32),
+            _ => Classic.on_move_finished(board, score),
+        }
+    }
+
+    /// `is_game_over(board)` returns a bool.
+    fn is_game_over(&self, board: &Board) -> bool {
+        const HOOK: &str = "is_game_over";
+
+        match self.call(HOOK, (board_to_array(board),)) {
+            Some(result) => self.cast::<bool>(HOOK, result),
+            None => None,
+        }
+        .unwrap_or_else(|| {
+            // The merges of the script still apply when checking for moves.
+            board.legal_moves_with(self).is_empty()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_2048::rules::Direction;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn compile(source: &str) -> (ScriptRules, ScriptErrors) {
+        let errors = ScriptErrors::default();
+        let rules = ScriptRules::compile("test", source, errors.clone()).unwrap();
+        (rules, errors)
+    }
+
+    fn error_count(errors: &ScriptErrors) -> usize {
+        errors.0.lock().unwrap().len()
+    }
+
+    #[test]
+    fn compile_error_is_reported() {
+        let errors = ScriptErrors::default();
+        assert!(ScriptRules::compile("test", "fn on_spawn(", errors.clone()).is_none());
+        assert_eq!(error_count(&errors), 1);
+    }
+
+    #[test]
+    fn missing_hooks_keep_the_classic_rules() {
+        let (rules, errors) = compile("");
+        assert!(rules.can_merge(3, 3));
+        assert!(!rules.can_merge(3, 2));
+        assert_eq!(rules.merge_result(2, 2), Some(3));
+        assert_eq!(rules.merge_result(9, 9), None);
+        assert_eq!(error_count(&errors), 0);
+    }
+
+    #[test]
+    fn hooks_change_the_rules() {
+        let (rules, errors) = compile(
+            "fn merge_result(into, level) { into + 2 }
+             fn on_spawn(board) { #{ row: 0, col: 0, level: 1 } }",
+        );
+        assert_eq!(rules.merge_result(2, 2), Some(4));
+
+        let board = Board::new(4);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(rules.spawn(&board, &mut rng), Some((0, 0, 1)));
+        assert_eq!(error_count(&errors), 0);
+    }
+
+    #[test]
+    fn levels_out_of_range_are_rejected() {
+        let (rules, errors) = compile("fn merge_result(into, level) { 31 }");
+        assert_eq!(rules.merge_result(2, 2), Some(3));
+        assert_eq!(error_count(&errors), 1);
+
+        let (rules, errors) = compile("fn merge_result(into, level) { -1 }");
+        assert_eq!(rules.merge_result(9, 9), None);
+        assert_eq!(error_count(&errors), 1);
+
+        let (rules, errors) = compile("fn on_spawn(board) { #{ row: 0, col: 0, level: 40 } }");
+        let mut rng = StdRng::seed_from_u64(0);
+        let (_, _, level) = rules.spawn(&Board::new(4), &mut rng).unwrap();
+        assert!(level <= 1);
+        assert_eq!(error_count(&errors), 2);
+    }
+
+    #[test]
+    fn high_levels_dont_overflow_the_score() {
+        let (rules, _) = compile("fn merge_result(into, level) { 30 }");
+        let mut board = Board::new(4);
+        board.set(0, 0, Some(29));
+        board.set(0, 1, Some(29));
+        board.set(1, 0, Some(29));
+        board.set(1, 1, Some(29));
+
+        let result = board.slide_with(Direction::Left, &rules);
+        assert_eq!(result.score, u32::MAX);
+    }
+
+    #[test]
+    fn failing_hooks_are_reported() {
+        let (rules, errors) = compile("fn can_merge(into, level) { 1 }");
+        assert!(rules.can_merge(3, 3));
+        assert_eq!(error_count(&errors), 1);
+
+        let (rules, errors) = compile("fn is_game_over(board) { throw \"oops\"; }");
+        let mut board = Board::new(4);
+        board.set(0, 0, Some(0));
+        assert!(!rules.is_game_over(&board));
+        assert_eq!(error_count(&errors), 1);
+    }
+}
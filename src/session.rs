@@ -0,0 +1,322 @@
+//! This module contains the implementation of the game sessions,
+//! the transitions between the main menu and the board.
+
+use bevy::prelude::*;
+use bevy_2048::rules;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, io};
+
+use crate::{
+    common::{
+        fulldir, rules_board, AppState, GameEvent, GameRules, GameSize, GameState, Position, Tile,
+        MAX_CELLS, MIN_CELLS,
+    },
+    locale::{Locale, LocalizedText},
+    movement::{GameWon, InputQueue, MovePlan, MovingState},
+    score::{GameStats, Score},
+    scripting::{load_rules, GameMode, ScriptErrors},
+    theme::Theme,
+    tile_spawning::{spawn_tile, DespawnAnimation, POST_SPAWN_STAGE},
+    ui::ToastEvent,
+};
+
+/// The file in the data directory the left game is saved into.
+const SAVED_GAME_FILE: &str = "saved_game.json";
+
+/// This plugin builds the sessions into the app.
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.add_event::<SessionEvent>()
+            .init_resource::<LastGame>()
+            // These systems despawn and spawn tiles, so they run after
+            // all the other systems are done with the tiles.
+            .add_system_to_stage(POST_SPAWN_STAGE, leave_game.system())
+            .add_system_to_stage(POST_SPAWN_STAGE, start_game.system());
+    }
+}
+
+/// Event sent by the menus to move between the main menu and the board.
+#[derive(Debug, Clone)]
+pub enum SessionEvent {
+    /// Starts a new game of a mode on a board with `size` rows and columns.
+    Start { mode: String, size: usize },
+    /// Continues the game that was left.
+    Continue,
+    /// Leaves the current game for the main menu.
+    Leave,
+}
+
+/// The state of a game that was left, so it can be continued.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub mode: String,
+    pub size: usize,
+    pub score: u32,
+    pub moves: u32,
+    pub previous_highscore: u32,
+    /// The tiles' levels, row after row from the bottom.
+    pub cells: Vec<Option<u32>>,
+}
+
+impl SavedGame {
+    /// Checks that the saved board can be played, a corrupt or hand-edited file may not.
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_CELLS..=MAX_CELLS).contains(&self.size) {
+            return Err(format!(
+                "The size {} is out of {}..={}",
+                self.size, MIN_CELLS, MAX_CELLS
+            ));
+        }
+        if self.cells.len() != self.size * self.size {
+            return Err(format!(
+                "{} cells don't fill a board of size {}",
+                self.cells.len(),
+                self.size
+            ));
+        }
+        if let Some(level) = self
+            .cells
+            .iter()
+            .flatten()
+            .find(|&&level| level > rules::MAX_RULES_LEVEL)
+        {
+            return Err(format!(
+                "The level {} is out of 0..={}",
+                level,
+                rules::MAX_RULES_LEVEL
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// This struct saves the game that was left into `saved_game.json` in the data directory.
+#[derive(Debug)]
+pub struct LastGame(Option<SavedGame>);
+
+impl Default for LastGame {
+    /// Trys to load the left game from the file.
+    fn default() -> Self {
+        match Self::load() {
+            Ok(game) => Self(game),
+            Err(e) => {
+                eprintln!("Couldn't load the saved game: {}", e);
+                Self(None)
+            }
+        }
+    }
+}
+
+impl LastGame {
+    /// Checks if there is a game to continue.
+    pub fn exists(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Loads the game from the file, `None` if no game was saved.
+    fn load() -> Result<Option<SavedGame>, Box<dyn Error>> {
+        match fs::read_to_string(fulldir(SAVED_GAME_FILE, false)?) {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Replaces the game and saves it into the file.
+    /// Errors are printed into the console.
+    fn set(&mut self, game: Option<SavedGame>) {
+        let result = fulldir(SAVED_GAME_FILE, true).and_then(|filepath| {
+            match &game {
+                Some(game) => fs::write(filepath, serde_json::to_string_pretty(game)?)?,
+                None => match fs::remove_file(filepath) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                },
+            }
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            eprintln!("Couldn't save the game: {}", e);
+        }
+
+        self.0 = game;
+    }
+}
+
+/// Leaves the game for the main menu.
+/// The game is saved unless it's over, and all the tiles are despawned.
+#[allow(clippy::too_many_arguments)]
+fn leave_game(
+    mut commands: Commands,
+    mut reader: Local<EventReader<SessionEvent>>,
+    session_events: Res<Events<SessionEvent>>,
+    (mut app_state, mut game_state): (ResMut<AppState>, ResMut<GameState>),
+    (mut moving_state, mut move_plan, mut input_queue): (
+        ResMut<MovingState>,
+        ResMut<MovePlan>,
+        ResMut<InputQueue>,
+    ),
+    (mode, game_size, score, stats): (Res<GameMode>, Res<GameSize>, Res<Score>, Res<GameStats>),
+    mut last_game: ResMut<LastGame>,
+    tiles: Query<(Entity, &Tile, &Position)>,
+    despawning: Query<With<DespawnAnimation, Entity>>,
+) {
+    if !reader
+        .iter(&session_events)
+        .any(|event| matches!(event, SessionEvent::Leave))
+    {
+        return;
+    }
+
+    if matches!(*game_state, GameState::GameOver) {
+        last_game.set(None);
+    } else {
+        let board = rules_board(game_size.cells(), tiles.iter().map(|(_, t, p)| (t, p)));
+        last_game.set(Some(SavedGame {
+            mode: mode.0.clone(),
+            size: game_size.cells(),
+            score: score.0,
+            moves: stats.moves,
+            previous_highscore: stats.previous_highscore,
+            cells: board.cells().to_vec(),
+        }));
+    }
+
+    // The tiles are despawned right away, so no tile is left behind the menu.
+    for (entity, _, _) in tiles.iter() {
+        commands.despawn(entity);
+    }
+    for entity in despawning.iter() {
+        commands.despawn(entity);
+    }
+
+    // A move that was in the middle of its animation is dropped.
+    *moving_state = MovingState::Idle;
+    *move_plan = MovePlan::default();
+    input_queue.clear();
+
+    *game_state = GameState::Play;
+    *app_state = AppState::Menu;
+}
+
+/// Starts a new game or continues the left game.
+/// A left game that can't be played is discarded with a toast.
+#[allow(clippy::too_many_arguments)]
+fn start_game(
+    mut commands: Commands,
+    mut reader: Local<EventReader<SessionEvent>>,
+    session_events: Res<Events<SessionEvent>>,
+    (mut app_state, mut game_state): (ResMut<AppState>, ResMut<GameState>),
+    (mut mode, mut rules, errors): (ResMut<GameMode>, ResMut<GameRules>, Res<ScriptErrors>),
+    mut game_size: ResMut<GameSize>,
+    (mut score, mut stats, mut won): (ResMut<Score>, ResMut<GameStats>, ResMut<GameWon>),
+    mut last_game: ResMut<LastGame>,
+    (theme, mut materials): (Res<Theme>, ResMut<Assets<ColorMaterial>>),
+    mut game_events: ResMut<Events<GameEvent>>,
+    (locale, mut toasts): (Res<Locale>, ResMut<Events<ToastEvent>>),
+) {
+    for event in reader.iter(&session_events) {
+        match event {
+            SessionEvent::Start { mode: name, size } => {
+                *rules = load_rules(name, &errors);
+                mode.0 = name.clone();
+                game_size.set_cells(*size);
+                last_game.set(None);
+
+                *app_state = AppState::Playing;
+                // The tiles are spawned by the new game.
+                *game_state = GameState::Restarting;
+            }
+            SessionEvent::Continue => {
+                let saved = match last_game.0.take() {
+                    Some(saved) => saved,
+                    None => continue,
+                };
+                last_game.set(None);
+
+                if let Err(e) = saved.validate() {
+                    eprintln!("Couldn't continue the saved game: {}", e);
+                    toasts.send(ToastEvent::new(
+                        locale.render(&LocalizedText::new("saved-game-invalid")),
+                    ));
+                    continue;
+                }
+
+                *rules = load_rules(&saved.mode, &errors);
+                mode.0 = saved.mode;
+                game_size.set_cells(saved.size);
+
+                for (index, level) in saved.cells.iter().enumerate() {
+                    if let Some(level) = *level {
+                        let position = Position {
+                            row: index / saved.size,
+                            col: index % saved.size,
+                        };
+                        spawn_tile(
+                            &mut commands,
+                            &mut materials,
+                            &theme,
+                            *game_size,
+                            Tile { level },
+                            position,
+                        );
+                    }
+                }
+
+                // A game that already has the winning tile isn't celebrated again.
+                won.0 = saved
+                    .cells
+                    .iter()
+                    .flatten()
+                    .any(|&level| rules::tile_score(level) >= rules.win_score());
+                score.0 = saved.score;
+                stats.moves = saved.moves;
+                stats.previous_highscore = saved.previous_highscore;
+                game_events.send(GameEvent::ScoreChanged { score: saved.score });
+
+                *app_state = AppState::Playing;
+                *game_state = GameState::Play;
+            }
+            SessionEvent::Leave => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(size: usize, cells: Vec<Option<u32>>) -> SavedGame {
+        SavedGame {
+            mode: "classic".to_string(),
+            size,
+            score: 0,
+            moves: 0,
+            previous_highscore: 0,
+            cells,
+        }
+    }
+
+    #[test]
+    fn valid_save_is_accepted() {
+        let mut cells = vec![None; 16];
+        cells[0] = Some(0);
+        cells[5] = Some(rules::MAX_LEVEL);
+        assert!(saved(4, cells).validate().is_ok());
+    }
+
+    #[test]
+    fn corrupt_saves_are_rejected() {
+        assert!(saved(0, Vec::new()).validate().is_err());
+        assert!(saved(MAX_CELLS + 1, vec![None; 81]).validate().is_err());
+        assert!(saved(4, vec![None; 15]).validate().is_err());
+
+        let mut cells = vec![None; 16];
+        cells[3] = Some(rules::MAX_RULES_LEVEL + 1);
+        assert!(saved(4, cells).validate().is_err());
+    }
+}
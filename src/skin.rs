@@ -0,0 +1,354 @@
+//! This module contains the implementation of the tile skins.
+//! A skin is loaded from `assets/skins/<name>.ron` and draws the tiles, the board and
+//! the empty cells with images instead of the theme's flat colors.
+//! Anything the skin has no image for keeps the theme's colors.
+
+use bevy::{asset::LoadState, prelude::*};
+use serde::Deserialize;
+use std::{error::Error, fs};
+
+use crate::{
+    common::{asset_names, asset_path, Settings, Tile},
+    theme::{Theme, ThemeMaterials},
+    ui::ErrorEvent,
+};
+
+/// This plugin builds the skins into the app.
+pub struct SkinPlugin;
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<Skin>()
+            .add_system(switch_skin.system())
+            .add_system(load_skin_images.system())
+            .add_system_to_stage(stage::POST_UPDATE, apply_skin.system());
+    }
+}
+
+/// The file of a skin, the images' paths are relative to `assets/skins`.
+#[derive(Debug, Deserialize)]
+struct SkinFile {
+    name: String,
+    /// An image with a sprite for each level, from the top left corner row after row.
+    tiles: String,
+    /// The width and the height of each sprite in pixels.
+    sprite_size: u32,
+    #[serde(default)]
+    board: Option<String>,
+    #[serde(default)]
+    empty_cell: Option<String>,
+}
+
+/// The images of a skin.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum SkinImage {
+    Tiles,
+    Board,
+    EmptyCell,
+}
+
+impl SkinImage {
+    /// Returns the name of the image in error messages.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Tiles => "tiles",
+            Self::Board => "board",
+            Self::EmptyCell => "empty cell",
+        }
+    }
+}
+
+/// The images the tiles, the board and the empty cells are drawn with.
+#[derive(Debug)]
+pub struct Skin {
+    /// The name of the skin's file, `None` when the tiles are drawn with flat colors.
+    pub id: Option<String>,
+    /// The name that is shown to the player.
+    pub name: String,
+    sprite_size: u32,
+    /// The images that are still loading.
+    loading: Vec<(SkinImage, Handle<Texture>)>,
+    /// The sprites of the levels, cut from the tiles' image.
+    tiles: Vec<Handle<Texture>>,
+    board: Option<Handle<Texture>>,
+    empty_cell: Option<Handle<Texture>>,
+}
+
+impl FromResources for Skin {
+    /// Loads the skin that is chosen in the settings.
+    /// If it fails the tiles are drawn with flat colors.
+    fn from_resources(resources: &Resources) -> Self {
+        let settings = resources.get::<Settings>().unwrap();
+        let assets = resources.get::<AssetServer>().unwrap();
+
+        let name = match &settings.skin {
+            Some(name) => name,
+            None => return Self::flat(),
+        };
+
+        match Self::load(name, &assets) {
+            Ok((skin, problems)) => {
+                for problem in problems {
+                    eprintln!("{}", problem);
+                }
+                skin
+            }
+            Err(e) => {
+                eprintln!("Couldn't load skin `{}`: {}", name, e);
+                Self::flat()
+            }
+        }
+    }
+}
+
+impl Skin {
+    /// Creates the skin that draws everything with flat colors.
+    pub fn flat() -> Self {
+        Self {
+            id: None,
+            name: "None".to_string(),
+            sprite_size: 0,
+            loading: Vec::new(),
+            tiles: Vec::new(),
+            board: None,
+            empty_cell: None,
+        }
+    }
+
+    /// Loads the skin from `assets/skins/<name>.ron` and starts loading its images.
+    /// Images that are missing are returned as problems, and are drawn with flat colors.
+    pub fn load(name: &str, assets: &AssetServer) -> Result<(Self, Vec<String>), Box<dyn Error>> {
+        let source = fs::read_to_string(asset_path("skins", &format!("{}.ron", name)))?;
+        let file: SkinFile = ron::de::from_str(&source)?;
+        if file.sprite_size == 0 {
+            return Err("The sprites' size must be positive".into());
+        }
+
+        let mut skin = Self {
+            id: Some(name.to_string()),
+            name: file.name,
+            sprite_size: file.sprite_size,
+            ..Self::flat()
+        };
+
+        let mut problems = Vec::new();
+        let images = [
+            (SkinImage::Tiles, Some(file.tiles)),
+            (SkinImage::Board, file.board),
+            (SkinImage::EmptyCell, file.empty_cell),
+        ];
+        for (image, path) in images.iter() {
+            let path = match path {
+                Some(path) => path,
+                None => continue,
+            };
+
+            // A missing image would never finish loading, so it's checked here.
+            if asset_path("skins", path).is_file() {
+                skin.loading
+                    .push((*image, assets.load(format!("skins/{}", path).as_str())));
+            } else {
+                problems.push(format!("Skin `{}` is missing the image `{}`", name, path));
+            }
+        }
+
+        Ok((skin, problems))
+    }
+
+    /// Paints a tile with the sprite of its level, or with the theme's color
+    /// if the skin has no sprite for it.
+    pub fn paint_tile(&self, material: &mut ColorMaterial, theme: &Theme, level: u32) {
+        paint(
+            material,
+            theme.tile_color(level),
+            self.tiles.get(level as usize),
+        );
+    }
+
+    /// Paints the board and the empty cells with their images, or with the theme's colors.
+    pub fn paint_board(
+        &self,
+        materials: &mut Assets<ColorMaterial>,
+        theme_materials: &ThemeMaterials,
+        theme: &Theme,
+    ) {
+        let parts = [
+            (&theme_materials.board, theme.board, &self.board),
+            (
+                &theme_materials.empty_cell,
+                theme.empty_cell,
+                &self.empty_cell,
+            ),
+        ];
+        for (handle, color, texture) in parts.iter() {
+            if let Some(material) = materials.get_mut(*handle) {
+                paint(material, *color, texture.as_ref());
+            }
+        }
+    }
+
+    /// Cuts the tiles' image into a sprite for each level.
+    fn cut_tiles(&self, image: &Texture) -> Result<Vec<Texture>, String> {
+        let pixel_size = image.format.pixel_size();
+        let sprite_size = self.sprite_size as usize;
+        let (columns, rows) = (
+            image.size.x() as usize / sprite_size,
+            image.size.y() as usize / sprite_size,
+        );
+        if columns == 0 || rows == 0 {
+            return Err(format!(
+                "The tiles' image is smaller than a sprite of {0}x{0} pixels",
+                sprite_size
+            ));
+        }
+
+        let image_row = image.size.x() as usize * pixel_size;
+        let sprite_row = sprite_size * pixel_size;
+        let sprites = (0..rows * columns)
+            .map(|index| {
+                let (row, column) = (index / columns, index % columns);
+                let mut data = Vec::with_capacity(sprite_row * sprite_size);
+                for y in row * sprite_size..(row + 1) * sprite_size {
+                    let start = y * image_row + column * sprite_row;
+                    data.extend_from_slice(&image.data[start..start + sprite_row]);
+                }
+
+                Texture::new(
+                    Vec2::new(sprite_size as f32, sprite_size as f32),
+                    data,
+                    image.format,
+                )
+            })
+            .collect();
+
+        Ok(sprites)
+    }
+}
+
+/// Paints a material with a texture, or with a flat color if there is no texture.
+fn paint(material: &mut ColorMaterial, color: Color, texture: Option<&Handle<Texture>>) {
+    match texture {
+        Some(texture) => {
+            // The texture's colors are shown as they are.
+            material.color = Color::WHITE;
+            material.texture = Some(texture.clone());
+        }
+        None => {
+            material.color = color;
+            material.texture = None;
+        }
+    }
+}
+
+/// Lists the skins in `assets/skins`.
+pub fn available_skins() -> Vec<String> {
+    asset_names("skins", "ron")
+}
+
+/// This system loads the skin when another skin is chosen in the settings.
+fn switch_skin(
+    settings: ChangedRes<Settings>,
+    assets: Res<AssetServer>,
+    mut skin: ResMut<Skin>,
+    mut error_events: ResMut<Events<ErrorEvent>>,
+) {
+    if settings.skin == skin.id {
+        return;
+    }
+
+    let name = match &settings.skin {
+        Some(name) => name,
+        None => {
+            *skin = Skin::flat();
+            return;
+        }
+    };
+
+    match Skin::load(name, &assets) {
+        Ok((loaded, problems)) => {
+            *skin = loaded;
+            for message in problems {
+                error_events.send(ErrorEvent { message });
+            }
+        }
+        Err(e) => error_events.send(ErrorEvent {
+            message: format!("Couldn't load skin `{}`: {}", name, e),
+        }),
+    }
+}
+
+/// This system gives the skin its images when they finish loading.
+/// Images that fail to load are reported, and the theme's colors are kept for them.
+fn load_skin_images(
+    assets: Res<AssetServer>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut skin: ResMut<Skin>,
+    mut error_events: ResMut<Events<ErrorEvent>>,
+) {
+    let finished = |handle: &Handle<Texture>| {
+        matches!(
+            assets.get_load_state(handle),
+            LoadState::Loaded | LoadState::Failed
+        )
+    };
+
+    // The skin is only changed when an image has finished, so it isn't repainted every frame.
+    if !skin.loading.iter().any(|(_, handle)| finished(handle)) {
+        return;
+    }
+
+    let (done, loading) = skin
+        .loading
+        .drain(..)
+        .partition::<Vec<_>, _>(|(_, handle)| finished(handle));
+    skin.loading = loading;
+
+    for (image, handle) in done {
+        if assets.get_load_state(&handle) == LoadState::Failed {
+            error_events.send(ErrorEvent {
+                message: format!(
+                    "Couldn't load the {} image of skin `{}`",
+                    image.name(),
+                    skin.name
+                ),
+            });
+            continue;
+        }
+
+        match image {
+            SkinImage::Tiles => {
+                let sprites = match textures.get(&handle) {
+                    Some(texture) => skin.cut_tiles(texture),
+                    None => continue,
+                };
+                match sprites {
+                    Ok(sprites) => {
+                        skin.tiles = sprites.into_iter().map(|s| textures.add(s)).collect();
+                    }
+                    Err(e) => error_events.send(ErrorEvent {
+                        message: format!("Couldn't use the tiles of skin `{}`: {}", skin.name, e),
+                    }),
+                }
+            }
+            SkinImage::Board => skin.board = Some(handle),
+            SkinImage::EmptyCell => skin.empty_cell = Some(handle),
+        }
+    }
+}
+
+/// This system repaints the tiles, the board and the empty cells when the skin changes.
+fn apply_skin(
+    skin: ChangedRes<Skin>,
+    theme: Res<Theme>,
+    theme_materials: Res<ThemeMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    tiles: Query<(&Tile, &Handle<ColorMaterial>)>,
+) {
+    skin.paint_board(&mut materials, &theme_materials, &theme);
+
+    for (tile, handle) in tiles.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            skin.paint_tile(material, &theme, tile.level);
+        }
+    }
+}
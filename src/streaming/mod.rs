@@ -0,0 +1,96 @@
+//! This module contains the streaming of a live game to spectators
+//! as newline-delimited JSON, and the spectator mode that renders it.
+
+mod protocol;
+mod server;
+mod spectator;
+
+pub use protocol::StreamMessage;
+pub use server::StreamServer;
+pub use spectator::SpectatorConnection;
+
+use bevy::prelude::*;
+use std::{fmt, str::FromStr};
+
+use crate::{common::GameEvent, tile_spawning::POST_SPAWN_STAGE};
+
+/// An address of a local socket to stream the game on.
+#[derive(Debug, Clone)]
+pub enum StreamAddress {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+impl FromStr for StreamAddress {
+    type Err = String;
+
+    /// Parses `tcp:<host>:<port>`, `<host>:<port>` or `unix:<path>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return Ok(Self::Unix(path.into()));
+            #[cfg(not(unix))]
+            return Err(format!("Unix sockets are not supported: `{}`", path));
+        }
+
+        let address = s.strip_prefix("tcp:").unwrap_or(s);
+        if address.contains(':') {
+            Ok(Self::Tcp(address.to_string()))
+        } else {
+            Err(format!("Invalid address `{}`", s))
+        }
+    }
+}
+
+impl fmt::Display for StreamAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "tcp:{}", address),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Event listener for GameEvent used by the stream server.
+#[derive(Default)]
+pub struct GameEventListener {
+    pub reader: EventReader<GameEvent>,
+}
+
+/// This plugin publishes the game on a socket.
+pub struct StreamPlugin(pub StreamAddress);
+
+impl Plugin for StreamPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        match StreamServer::bind(&self.0) {
+            Ok(server) => {
+                println!("Streaming the game on {}", self.0);
+
+                app.add_resource(server)
+                    .init_resource::<GameEventListener>()
+                    // Publishing after the game over check, so the whole move is sent.
+                    .add_system_to_stage(POST_SPAWN_STAGE, server::publish_events.system());
+            }
+            Err(e) => eprintln!("Couldn't stream the game on {}: {}", self.0, e),
+        }
+    }
+}
+
+/// This plugin renders a game streamed from another instance.
+pub struct SpectatorPlugin(pub StreamAddress);
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        match SpectatorConnection::connect(&self.0) {
+            Ok(connection) => {
+                println!("Spectating the game on {}", self.0);
+
+                app.add_resource(connection)
+                    .add_system(spectator::apply_messages.system());
+            }
+            Err(e) => eprintln!("Couldn't spectate the game on {}: {}", self.0, e),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! This module contains the messages sent to the spectators.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{GameEvent, Position, Tile},
+    movement::MovingDirection,
+};
+
+/// The state of a single tile in a snapshot.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct TileState {
+    pub row: usize,
+    pub col: usize,
+    pub level: u32,
+}
+
+impl TileState {
+    pub fn new(tile: &Tile, position: &Position) -> Self {
+        Self {
+            row: position.row,
+            col: position.col,
+            level: tile.level,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        Position {
+            row: self.row,
+            col: self.col,
+        }
+    }
+}
+
+fn default_size() -> usize {
+    4
+}
+
+/// A message sent to the spectators, serialized as a single JSON line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage {
+    /// The whole board, sent when a spectator connects and after every change.
+    Snapshot {
+        /// The number of cells in a row, older streams don't send it.
+        #[serde(default = "default_size")]
+        size: usize,
+        tiles: Vec<TileState>,
+        score: u32,
+        game_over: bool,
+    },
+    Spawn {
+        row: usize,
+        col: usize,
+        level: u32,
+    },
+    Slide {
+        direction: MovingDirection,
+    },
+    Blocked {
+        direction: MovingDirection,
+    },
+    Merge {
+        row: usize,
+        col: usize,
+        /// `null` if the merged tile has been removed.
+        level: Option<u32>,
+    },
+    Score {
+        score: u32,
+    },
+    Won,
+    GameOver,
+    NewGame,
+}
+
+impl From<GameEvent> for StreamMessage {
+    fn from(event: GameEvent) -> Self {
+        match event {
+            GameEvent::Spawned { position, level } => Self::Spawn {
+                row: position.row,
+                col: position.col,
+                level,
+            },
+            GameEvent::Slid { direction } => Self::Slide { direction },
+            GameEvent::Blocked { direction } => Self::Blocked { direction },
+            GameEvent::Merged { position, level } => Self::Merge {
+                row: position.row,
+                col: position.col,
+                level,
+            },
+            GameEvent::ScoreChanged { score } => Self::Score { score },
+            GameEvent::Won => Self::Won,
+            GameEvent::GameOver => Self::GameOver,
+            GameEvent::NewGame => Self::NewGame,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! This module contains the implementation of the stream server and its system.
+
+use bevy::prelude::*;
+use std::{
+    io::{self, Write},
+    net::TcpListener,
+    sync::{
+        mpsc::{self, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use super::{protocol::TileState, GameEventListener, StreamAddress, StreamMessage};
+use crate::{
+    common::{GameEvent, GameSize, GameState, Position, Tile},
+    score::Score,
+    tile_spawning::Despawn,
+};
+
+/// The number of lines that can wait for a spectator, a spectator
+/// that falls further behind is dropped so it can't hold the game back.
+const CLIENT_QUEUE: usize = 256;
+
+/// The lines waiting to be written to a spectator by its own thread.
+type Client = SyncSender<Arc<str>>;
+
+/// Starts a thread that writes the lines sent to a spectator,
+/// so a slow spectator never blocks the game.
+/// The thread stops when the spectator can't be reached or is dropped.
+fn spawn_writer(mut stream: impl Write + Send + 'static) -> Client {
+    let (sender, receiver) = mpsc::sync_channel::<Arc<str>>(CLIENT_QUEUE);
+    thread::spawn(move || {
+        for line in receiver {
+            if stream.write_all(line.as_bytes()).is_err() {
+                break;
+            }
+        }
+    });
+
+    sender
+}
+
+/// This struct accepts spectators in the background and
+/// publishes messages to all of them.
+pub struct StreamServer {
+    /// Spectators that already got the board's snapshot.
+    clients: Vec<Client>,
+    /// Spectators that have been accepted but didn't get a snapshot yet.
+    pending: Arc<Mutex<Vec<Client>>>,
+}
+
+impl StreamServer {
+    /// Binds a socket on the given address and starts
+    /// accepting spectators on a background thread.
+    pub fn bind(address: &StreamAddress) -> io::Result<Self> {
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        let accepted = pending.clone();
+
+        match address {
+            StreamAddress::Tcp(address) => {
+                let listener = TcpListener::bind(address)?;
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        // Messages are small, so sending them right away is preferred.
+                        let _ = stream.set_nodelay(true);
+                        accepted.lock().unwrap().push(spawn_writer(stream));
+                    }
+                });
+            }
+            #[cfg(unix)]
+            StreamAddress::Unix(path) => {
+                // Removing a socket left by a previous run.
+                let _ = std::fs::remove_file(path);
+                let listener = std::os::unix::net::UnixListener::bind(path)?;
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        accepted.lock().unwrap().push(spawn_writer(stream));
+                    }
+                });
+            }
+        }
+
+        Ok(Self {
+            clients: Vec::new(),
+            pending,
+        })
+    }
+
+    /// Sends a message to all the spectators.
+    /// Spectators that can't be reached anymore or have fallen behind are dropped.
+    fn broadcast(&mut self, message: &StreamMessage) {
+        let line = encode(message);
+        self.clients
+            .retain(|client| client.try_send(line.clone()).is_ok());
+    }
+}
+
+/// Serializes a message into a single line.
+fn encode(message: &StreamMessage) -> Arc<str> {
+    let mut line = serde_json::to_string(message).expect("Stream messages are always serializable");
+    line.push('\n');
+    line.into()
+}
+
+/// This system publishes the game events to the spectators.
+/// New spectators get a snapshot of the board first, and a snapshot
+/// is sent after every frame that changed the board.
+/// Tiles that are being despawned are left out, they are already gone from the game.
+pub fn publish_events(
+    mut server: ResMut<StreamServer>,
+    mut listener: ResMut<GameEventListener>,
+    game_events: Res<Events<GameEvent>>,
+    game_state: Res<GameState>,
+    score: Res<Score>,
+    game_size: Res<GameSize>,
+    tiles: Query<Without<Despawn, (&Tile, &Position)>>,
+) {
+    let snapshot = StreamMessage::Snapshot {
+        size: game_size.cells(),
+        tiles: tiles
+            .iter()
+            .map(|(tile, position)| TileState::new(tile, position))
+            .collect(),
+        score: score.0,
+        game_over: matches!(*game_state, GameState::GameOver),
+    };
+
+    // Accepting the new spectators.
+    let pending: Vec<Client> = server.pending.lock().unwrap().drain(..).collect();
+    let line = encode(&snapshot);
+    for client in pending {
+        if client.try_send(line.clone()).is_ok() {
+            server.clients.push(client);
+        }
+    }
+
+    let mut changed = false;
+    for event in listener.reader.iter(&game_events) {
+        server.broadcast(&StreamMessage::from(*event));
+        changed = true;
+    }
+
+    if changed {
+        server.broadcast(&snapshot);
+    }
+}
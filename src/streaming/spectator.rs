@@ -0,0 +1,184 @@
+//! This module contains the implementation of the spectator connection and its system.
+
+use bevy::prelude::*;
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read},
+    net::TcpStream,
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex,
+    },
+    thread,
+};
+
+use super::{protocol::TileState, StreamAddress, StreamMessage};
+use crate::{
+    common::{GameSize, GameState, Position, Tile, MAX_CELLS, MIN_CELLS},
+    locale::Locale,
+    movement::MergeAnimation,
+    score::Score,
+    theme::Theme,
+    tile_spawning::{spawn_tile, Despawn},
+    ui::ToastEvent,
+};
+
+/// This struct receives the messages of a streamed game in the background.
+pub struct SpectatorConnection {
+    receiver: Mutex<Receiver<StreamMessage>>,
+}
+
+impl SpectatorConnection {
+    /// Connects to a streamed game and starts reading
+    /// its messages on a background thread.
+    pub fn connect(address: &StreamAddress) -> io::Result<Self> {
+        let stream: Box<dyn Read + Send> = match address {
+            StreamAddress::Tcp(address) => Box::new(TcpStream::connect(address)?),
+            #[cfg(unix)]
+            StreamAddress::Unix(path) => Box::new(std::os::unix::net::UnixStream::connect(path)?),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+
+                match serde_json::from_str(&line) {
+                    Ok(message) => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => eprintln!("Couldn't read a stream message: {}", e),
+                }
+            }
+
+            eprintln!("The streamed game has been disconnected");
+        });
+
+        Ok(Self {
+            receiver: Mutex::new(receiver),
+        })
+    }
+}
+
+/// Checks that a snapshot's board can be drawn: its size is one the game
+/// supports and every tile is inside it, so no two tiles share an index.
+fn is_valid_snapshot(size: usize, tiles: &[TileState]) -> bool {
+    (MIN_CELLS..=MAX_CELLS).contains(&size)
+        && tiles.iter().all(|tile| tile.row < size && tile.col < size)
+}
+
+/// This system applies the received messages to the board.
+/// The board is synchronized with the latest snapshot, tiles that changed
+/// their level are animated as merged and new tiles are animated as spawned.
+/// Snapshots with an invalid board are skipped with a toast.
+pub fn apply_messages(
+    mut commands: Commands,
+    connection: Res<SpectatorConnection>,
+    (locale, mut toasts): (Res<Locale>, ResMut<Events<ToastEvent>>),
+    mut game_size: ResMut<GameSize>,
+    (theme, mut materials): (Res<Theme>, ResMut<Assets<ColorMaterial>>),
+    (mut score, mut game_state): (ResMut<Score>, ResMut<GameState>),
+    mut tiles: Query<(Entity, &mut Tile, &Position)>,
+) {
+    // Only the latest snapshot matters.
+    let mut snapshot = None;
+    for message in connection.receiver.lock().unwrap().try_iter() {
+        match message {
+            StreamMessage::Snapshot {
+                size,
+                tiles,
+                score: snapshot_score,
+                game_over,
+            } => {
+                if !is_valid_snapshot(size, &tiles) {
+                    eprintln!("Skipped a stream snapshot with an invalid board");
+                    toasts.send(ToastEvent::new(locale.text("stream-invalid-snapshot")));
+                    continue;
+                }
+
+                game_size.set_cells(size);
+                score.0 = snapshot_score;
+                *game_state = if game_over {
+                    GameState::GameOver
+                } else {
+                    GameState::Play
+                };
+                snapshot = Some(tiles);
+            }
+            StreamMessage::Score { score: new_score } => score.0 = new_score,
+            StreamMessage::GameOver => *game_state = GameState::GameOver,
+            StreamMessage::NewGame => *game_state = GameState::Play,
+            // The other events are applied through the following snapshot.
+            _ => (),
+        }
+    }
+
+    if let Some(snapshot) = snapshot {
+        let cells = game_size.cells();
+        let mut remote: HashMap<usize, TileState> = snapshot
+            .into_iter()
+            .map(|tile| (tile.position().index(cells), tile))
+            .collect();
+
+        for (entity, mut tile, position) in tiles.iter_mut() {
+            match remote.remove(&position.index(cells)) {
+                // Tiles that doesn't exist anymore are despawned.
+                None => {
+                    commands.insert_one(entity, Despawn);
+                }
+                Some(remote_tile) if remote_tile.level != tile.level => {
+                    if remote_tile.level > tile.level {
+                        commands.insert_one(entity, MergeAnimation::default());
+                    }
+
+                    tile.level = remote_tile.level;
+                }
+                Some(_) => (),
+            }
+        }
+
+        // Spawning the tiles that are missing.
+        for remote_tile in remote.values() {
+            let tile = Tile {
+                level: remote_tile.level,
+            };
+            spawn_tile(
+                &mut commands,
+                &mut materials,
+                &theme,
+                *game_size,
+                tile,
+                remote_tile.position(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(row: usize, col: usize) -> TileState {
+        TileState { row, col, level: 1 }
+    }
+
+    #[test]
+    fn snapshot_size_is_checked() {
+        assert!(is_valid_snapshot(4, &[tile(0, 0), tile(3, 3)]));
+        assert!(!is_valid_snapshot(0, &[]));
+        assert!(!is_valid_snapshot(MIN_CELLS - 1, &[]));
+        assert!(!is_valid_snapshot(MAX_CELLS + 1, &[]));
+    }
+
+    #[test]
+    fn snapshot_tiles_are_inside_the_board() {
+        // (0, 4) would have the same index as (1, 0) on a 4x4 board.
+        assert!(!is_valid_snapshot(4, &[tile(1, 0), tile(0, 4)]));
+        assert!(!is_valid_snapshot(4, &[tile(4, 0)]));
+    }
+}
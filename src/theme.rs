@@ -0,0 +1,445 @@
+//! This module contains the implementation of the color themes.
+//! A theme is loaded from `assets/themes/<name>.ron` and is chosen in the settings.
+//! Switching the theme recolors every entity that is already spawned.
+
+use bevy::{prelude::*, render::pass::ClearColor};
+use serde::{de, Deserialize, Deserializer};
+use std::{error::Error, fs};
+
+use crate::{
+    common::{asset_names, asset_path, Settings, Tile},
+    skin::Skin,
+    ui::ErrorEvent,
+};
+
+/// The theme used when the chosen theme can't be loaded.
+const FALLBACK_THEME: (&str, &str) = ("rainbow", include_str!("../assets/themes/rainbow.ron"));
+
+/// This plugin builds the themes into the app.
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<Theme>()
+            .init_resource::<ThemeMaterials>()
+            .add_system(switch_theme.system())
+            // The tiles' levels are changed during the update stage.
+            .add_system_to_stage(stage::POST_UPDATE, apply_theme.system())
+            .add_system_to_stage(stage::POST_UPDATE, color_tiles.system());
+    }
+}
+
+/// The colors and the font of the game.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Theme {
+    /// The name of the theme's file.
+    #[serde(skip)]
+    pub id: String,
+    /// The name that is shown to the player.
+    pub name: String,
+    /// The path of the font in the assets.
+    pub font: String,
+    #[serde(skip)]
+    pub font_handle: Handle<Font>,
+    #[serde(deserialize_with = "hex_color")]
+    pub background: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub board: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub empty_cell: Color,
+    /// The colors of the tiles by their level, higher levels get generated colors.
+    #[serde(deserialize_with = "hex_colors")]
+    pub tiles: Vec<Color>,
+    /// The colors of the numbers on the tiles by their level, like the tiles' colors.
+    #[serde(deserialize_with = "hex_colors")]
+    pub tile_texts: Vec<Color>,
+    /// The color of the texts over the background.
+    #[serde(deserialize_with = "hex_color")]
+    pub text: Color,
+    /// The color of the panels, like the score and the toasts.
+    #[serde(deserialize_with = "hex_color")]
+    pub panel: Color,
+    /// The color of the texts over the panels and the buttons.
+    #[serde(deserialize_with = "hex_color")]
+    pub panel_text: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub button: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub button_hovered: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub button_pressed: Color,
+    /// The color of the game over overlay over the board.
+    #[serde(deserialize_with = "hex_color")]
+    pub overlay: Color,
+    /// The color that dims the screen behind the menus.
+    #[serde(deserialize_with = "hex_color")]
+    pub dim: Color,
+    /// The color of the error panel.
+    #[serde(deserialize_with = "hex_color")]
+    pub error: Color,
+}
+
+impl FromResources for Theme {
+    /// Loads the theme that is chosen in the settings.
+    /// If it fails it uses the fallback theme.
+    fn from_resources(resources: &Resources) -> Self {
+        let settings = resources.get::<Settings>().unwrap();
+        let assets = resources.get::<AssetServer>().unwrap();
+
+        Self::load(&settings.theme, &assets).unwrap_or_else(|e| {
+            eprintln!("Couldn't load theme `{}`: {}", settings.theme, e);
+            let (id, source) = FALLBACK_THEME;
+            Self::parse(id, source, &assets).expect("The fallback theme is valid")
+        })
+    }
+}
+
+impl Theme {
+    /// Loads the theme from `assets/themes/<name>.ron`.
+    pub fn load(name: &str, assets: &AssetServer) -> Result<Self, Box<dyn Error>> {
+        let source = fs::read_to_string(asset_path("themes", &format!("{}.ron", name)))?;
+        Self::parse(name, &source, assets)
+    }
+
+    /// Parses a theme and loads its font.
+    fn parse(id: &str, source: &str, assets: &AssetServer) -> Result<Self, Box<dyn Error>> {
+        let mut theme: Self = ron::de::from_str(source)?;
+        if theme.tiles.is_empty() || theme.tile_texts.is_empty() {
+            return Err("The theme has no tile colors".into());
+        }
+
+        theme.id = id.to_string();
+        theme.font_handle = assets.load(theme.font.as_str());
+        Ok(theme)
+    }
+
+    /// Returns the color of a tile by its level.
+    /// Levels that the theme doesn't define get a generated color.
+    pub fn tile_color(&self, level: u32) -> Color {
+        match self.tiles.get(level as usize) {
+            Some(&color) => color,
+            None => generated_tile_color(level, self.tile_text_color(level)),
+        }
+    }
+
+    /// Returns the color of a tile's number by its level.
+    pub fn tile_text_color(&self, level: u32) -> Color {
+        let index = (level as usize).min(self.tile_texts.len() - 1);
+        self.tile_texts[index]
+    }
+
+    /// Returns the color of a text by its role.
+    pub fn text_color(&self, text: ThemeText) -> Color {
+        match text {
+            ThemeText::Text => self.text,
+            ThemeText::Panel => self.panel_text,
+        }
+    }
+}
+
+/// Lists the themes in `assets/themes`.
+pub fn available_themes() -> Vec<String> {
+    asset_names("themes", "ron")
+}
+
+/// The contrast the generated colors keep with the tiles' numbers, as in the WCAG.
+const MIN_CONTRAST: f32 = 4.5;
+/// The hue turns by the golden angle from a level to the next, so close levels get distant hues.
+const GOLDEN_ANGLE: f32 = 137.507_77;
+
+/// Generates the color of a level, the same level always gets the same color.
+/// The lightness moves away from the number's color until their contrast is high enough.
+fn generated_tile_color(level: u32, text: Color) -> Color {
+    let hue = (level as f32 * GOLDEN_ANGLE) % 360.0;
+    // The saturation changes between levels too, so levels with close hues still differ.
+    let saturation = if level.is_multiple_of(2) { 0.75 } else { 0.55 };
+
+    let text_luminance = luminance(text);
+    // Above this luminance a text stands out more on black than on white.
+    let step: f32 = if text_luminance > 0.179 { -0.05 } else { 0.05 };
+
+    // The lightness is counted in steps from the middle, so adding up the steps can't
+    // stop it short of black or white, which always have the contrast.
+    let mut color = hsl(hue, saturation, 0.5);
+    for steps in 1..=10 {
+        if contrast(luminance(color), text_luminance) >= MIN_CONTRAST {
+            break;
+        }
+        let lightness = (0.5 + step * steps as f32).clamp(0.0, 1.0);
+        color = hsl(hue, saturation, lightness);
+    }
+
+    color
+}
+
+/// Converts a color from its hue in degrees, saturation and lightness.
+fn hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let m = lightness - chroma / 2.0;
+    Color::rgb(r + m, g + m, b + m)
+}
+
+/// Calculates the relative luminance of a color, as in the WCAG.
+fn luminance(color: Color) -> f32 {
+    0.2126 * color.r_linear() + 0.7152 * color.g_linear() + 0.0722 * color.b_linear()
+}
+
+/// Calculates the contrast ratio between two luminances, from 1 to 21.
+fn contrast(a: f32, b: f32) -> f32 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Parses a color written as `#rrggbb` or `#rrggbbaa`.
+fn parse_color(value: &str) -> Result<Color, String> {
+    let hex = value
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 || hex.len() == 8)
+        .ok_or_else(|| {
+            format!(
+                "Invalid color `{}`, expected `#rrggbb` or `#rrggbbaa`",
+                value
+            )
+        })?;
+
+    let mut channels = [255; 4];
+    for (channel, i) in channels.iter_mut().zip((0..hex.len()).step_by(2)) {
+        *channel = u8::from_str_radix(&hex[i..i + 2], 16)
+            .map_err(|_| format!("Invalid color `{}`", value))?;
+    }
+
+    Ok(Color::rgba_u8(
+        channels[0],
+        channels[1],
+        channels[2],
+        channels[3],
+    ))
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    parse_color(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+fn hex_colors<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Color>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|value| parse_color(value))
+        .collect::<Result<_, _>>()
+        .map_err(de::Error::custom)
+}
+
+/// The materials of the theme, shared by the board and the ui.
+/// Switching the theme changes their colors, so they never have to be replaced.
+pub struct ThemeMaterials {
+    pub background: Handle<ColorMaterial>,
+    pub board: Handle<ColorMaterial>,
+    pub empty_cell: Handle<ColorMaterial>,
+    pub panel: Handle<ColorMaterial>,
+    pub button: Handle<ColorMaterial>,
+    pub button_hovered: Handle<ColorMaterial>,
+    pub button_pressed: Handle<ColorMaterial>,
+    pub overlay: Handle<ColorMaterial>,
+    pub dim: Handle<ColorMaterial>,
+    pub error: Handle<ColorMaterial>,
+}
+
+impl FromResources for ThemeMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme = resources.get::<Theme>().unwrap();
+        let mut materials = resources.get_mut::<Assets<ColorMaterial>>().unwrap();
+        ThemeMaterials {
+            background: materials.add(theme.background.into()),
+            board: materials.add(theme.board.into()),
+            empty_cell: materials.add(theme.empty_cell.into()),
+            panel: materials.add(theme.panel.into()),
+            button: materials.add(theme.button.into()),
+            button_hovered: materials.add(theme.button_hovered.into()),
+            button_pressed: materials.add(theme.button_pressed.into()),
+            overlay: materials.add(theme.overlay.into()),
+            dim: materials.add(theme.dim.into()),
+            error: materials.add(theme.error.into()),
+        }
+    }
+}
+
+impl ThemeMaterials {
+    /// Returns every material with its color in a theme.
+    fn colors<'a>(&'a self, theme: &Theme) -> [(&'a Handle<ColorMaterial>, Color); 10] {
+        [
+            (&self.background, theme.background),
+            (&self.board, theme.board),
+            (&self.empty_cell, theme.empty_cell),
+            (&self.panel, theme.panel),
+            (&self.button, theme.button),
+            (&self.button_hovered, theme.button_hovered),
+            (&self.button_pressed, theme.button_pressed),
+            (&self.overlay, theme.overlay),
+            (&self.dim, theme.dim),
+            (&self.error, theme.error),
+        ]
+    }
+}
+
+/// Component of the texts, tells which color of the theme they use.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ThemeText {
+    /// A text over the background.
+    Text,
+    /// A text over a panel or a button.
+    Panel,
+}
+
+/// This system loads the theme when another theme is chosen in the settings.
+fn switch_theme(
+    settings: ChangedRes<Settings>,
+    assets: Res<AssetServer>,
+    mut theme: ResMut<Theme>,
+    mut error_events: ResMut<Events<ErrorEvent>>,
+) {
+    if settings.theme == theme.id {
+        return;
+    }
+
+    match Theme::load(&settings.theme, &assets) {
+        Ok(loaded) => *theme = loaded,
+        Err(e) => error_events.send(ErrorEvent {
+            message: format!("Couldn't load theme `{}`: {}", settings.theme, e),
+        }),
+    }
+}
+
+/// This system recolors everything that is already spawned when the theme changes.
+/// The parts that the skin draws with images keep their images.
+#[allow(clippy::too_many_arguments)]
+fn apply_theme(
+    theme: ChangedRes<Theme>,
+    skin: Res<Skin>,
+    theme_materials: Res<ThemeMaterials>,
+    mut clear_color: ResMut<ClearColor>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    tiles: Query<(&Tile, &Handle<ColorMaterial>)>,
+    mut texts: Query<(&ThemeText, &mut Text)>,
+) {
+    clear_color.0 = theme.background;
+
+    for (handle, color) in theme_materials.colors(&theme).iter() {
+        if let Some(material) = materials.get_mut(*handle) {
+            material.color = *color;
+        }
+    }
+    skin.paint_board(&mut materials, &theme_materials, &theme);
+
+    for (tile, handle) in tiles.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            skin.paint_tile(material, &theme, tile.level);
+        }
+    }
+
+    for (theme_text, mut text) in texts.iter_mut() {
+        text.style.color = theme.text_color(*theme_text);
+        text.font = theme.font_handle.clone();
+    }
+}
+
+/// This system repaints the tiles whose level has changed.
+fn color_tiles(
+    theme: Res<Theme>,
+    skin: Res<Skin>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    tiles: Query<(Changed<Tile>, &Handle<ColorMaterial>)>,
+) {
+    for (tile, handle) in tiles.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            skin.paint_tile(material, &theme, tile.level);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_2048::rules::MAX_RULES_LEVEL;
+
+    /// Returns the hue of a color in degrees.
+    fn hue(color: Color) -> f32 {
+        let (r, g, b) = (color.r(), color.g(), color.b());
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        let sector = if max == r {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+        sector * 60.0
+    }
+
+    /// The colors of the tiles' numbers in every theme, and plain black and white.
+    fn text_colors() -> Vec<Color> {
+        let mut colors = vec![Color::BLACK, Color::WHITE];
+        for name in available_themes() {
+            let path = asset_path("themes", &format!("{}.ron", name));
+            let theme: Theme = ron::de::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+            colors.extend(theme.tile_texts);
+        }
+        colors
+    }
+
+    #[test]
+    fn generated_colors_contrast_with_the_numbers() {
+        for text in text_colors() {
+            for level in 0..=MAX_RULES_LEVEL {
+                let color = generated_tile_color(level, text);
+                let ratio = contrast(luminance(color), luminance(text));
+                assert!(
+                    ratio >= MIN_CONTRAST,
+                    "level {} has a contrast of {} with {:?}",
+                    level,
+                    ratio,
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generated_colors_are_the_same_every_time() {
+        for text in text_colors() {
+            for level in 0..=MAX_RULES_LEVEL {
+                assert_eq!(
+                    generated_tile_color(level, text),
+                    generated_tile_color(level, text)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn adjacent_levels_have_distant_hues() {
+        for text in [Color::BLACK, Color::WHITE].iter() {
+            for level in 0..MAX_RULES_LEVEL {
+                let a = hue(generated_tile_color(level, *text));
+                let b = hue(generated_tile_color(level + 1, *text));
+                let distance = (a - b).abs().min(360.0 - (a - b).abs());
+                assert!(
+                    distance > 90.0,
+                    "levels {} and {} are {} degrees apart",
+                    level,
+                    level + 1,
+                    distance
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! This module contains the implementation of the DespawnAnimation and its system.
 
-use crate::common::{Animation, GameSize};
+use crate::common::{Animation, GameClock, GameSize};
 use bevy::prelude::*;
+use bevy_2048::easing::Easing;
 
 /// Component used to animate the tiles despawning.
 pub struct DespawnAnimation {
@@ -9,10 +10,10 @@ pub struct DespawnAnimation {
 }
 
 impl Default for DespawnAnimation {
-    /// Sets the animation to finish after 3 updates.
+    /// Sets the animation to shrink the tile in 0.05 seconds.
     fn default() -> Self {
         Self {
-            animation: Animation::new(3),
+            animation: Animation::between(1.0, 0.0, 0.05, Easing::InCubic),
         }
     }
 }
@@ -20,15 +21,15 @@ impl Default for DespawnAnimation {
 /// Despawning with an animation all tiles that have a despawn animation.
 pub fn despawn_animation(
     mut commands: Commands,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     game_size: Res<GameSize>,
     entity: Entity,
     mut despawn_anim: Mut<DespawnAnimation>,
     mut sprite: Mut<Sprite>,
 ) {
-    if despawn_anim.animation.update(time.delta_seconds) {
+    if despawn_anim.animation.update(clock.animation_seconds) {
         // Updating the sprite size while the animation is not finished.
-        let size = game_size.tile_size() * despawn_anim.animation.rev_value();
+        let size = game_size.tile_size() * despawn_anim.animation.value();
         sprite.size.set_x(size);
         sprite.size.set_y(size);
     }
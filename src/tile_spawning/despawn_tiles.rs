@@ -3,7 +3,7 @@ use bevy::prelude::*;
 
 use crate::{
     common::{Position, Tile},
-    movement::{Merged, Moving},
+    movement::Moving,
 };
 
 use super::DespawnAnimation;
@@ -20,6 +20,5 @@ pub fn despawn_tiles(mut commands: Commands, entity: Entity, _: &Despawn) {
     commands.remove_one::<Position>(entity);
     commands.remove_one::<Despawn>(entity);
     commands.remove_one::<Option<Moving>>(entity);
-    commands.remove_one::<Option<Merged>>(entity);
     commands.insert_one(entity, DespawnAnimation::default());
 }
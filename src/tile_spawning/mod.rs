@@ -1,14 +1,17 @@
 //! This modlue contains the spawn tile event, despawn tile component, spawn/despawn animations
-//! and a plugin for adding all of this into the application.
+//! tiles' texts and patterns and a plugin for adding all of this into the application.
 
 mod despawn_animation;
 mod despawn_tiles;
 mod spawn_animation;
 mod spawn_tiles;
+mod tile_pattern;
+mod tile_text;
 
 pub use despawn_animation::DespawnAnimation;
 pub use despawn_tiles::Despawn;
 pub use spawn_animation::SpawnAnimation;
+pub use spawn_tiles::spawn_tile;
 pub use spawn_tiles::SpawnTileEvent;
 pub use spawn_tiles::SpawnTileListener;
 
@@ -16,6 +19,9 @@ pub use spawn_tiles::SpawnTileListener;
 pub static DESPAWN_STAGE: &str = "DESPAWN";
 pub static SPAWN_STAGE: &str = "SPAWN";
 pub static POST_SPAWN_STAGE: &str = "POST-SPAWN";
+/// The tiles' texts and patterns follow the tiles after they have moved,
+/// and before the ui is laid out.
+pub static TILE_OVERLAY_STAGE: &str = "TILE-OVERLAY";
 
 use bevy::prelude::*;
 /// This plugin builds the tile spawing/despawning into the application.
@@ -24,13 +30,20 @@ pub struct SpawnTilePlugin;
 impl Plugin for SpawnTilePlugin {
     fn build(&self, app: &mut bevy::prelude::AppBuilder) {
         app.init_resource::<SpawnTileListener>()
+            .init_resource::<tile_pattern::TilePatterns>()
             .add_stage(DESPAWN_STAGE)
             .add_stage_after(DESPAWN_STAGE, SPAWN_STAGE)
             .add_stage_after(SPAWN_STAGE, POST_SPAWN_STAGE)
+            .add_stage_after(stage::UPDATE, TILE_OVERLAY_STAGE)
             .add_event::<SpawnTileEvent>()
             .add_system_to_stage(SPAWN_STAGE, spawn_tiles::spawn_tiles.system())
             .add_system_to_stage(SPAWN_STAGE, spawn_animation::spawn_animation.system())
             .add_system_to_stage(DESPAWN_STAGE, despawn_tiles::despawn_tiles.system())
-            .add_system_to_stage(DESPAWN_STAGE, despawn_animation::despawn_animation.system());
+            .add_system_to_stage(DESPAWN_STAGE, despawn_animation::despawn_animation.system())
+            .add_system_to_stage(TILE_OVERLAY_STAGE, tile_text::update_tile_texts.system())
+            .add_system_to_stage(
+                TILE_OVERLAY_STAGE,
+                tile_pattern::update_tile_patterns.system(),
+            );
     }
 }
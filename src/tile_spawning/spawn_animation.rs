@@ -1,6 +1,7 @@
 //! This module contains the implementation of the SpawnAnimation component and its system.
-use crate::common::{Animation, GameSize};
+use crate::common::{Animation, GameClock, GameSize};
 use bevy::prelude::*;
+use bevy_2048::easing::Easing;
 
 /// Component used to animate the tiles spawning.
 pub struct SpawnAnimation {
@@ -8,10 +9,10 @@ pub struct SpawnAnimation {
 }
 
 impl Default for SpawnAnimation {
-    /// Sets the animation to finish after 3 updates.
+    /// Sets the animation to pop the tile in 0.1 seconds.
     fn default() -> Self {
         Self {
-            animation: Animation::new(3),
+            animation: Animation::new(0.1, Easing::OutBack),
         }
     }
 }
@@ -21,13 +22,13 @@ impl Default for SpawnAnimation {
 /// is removed from the entity.
 pub fn spawn_animation(
     mut commands: Commands,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     game_size: Res<GameSize>,
     entity: Entity,
     mut spawn_anim: Mut<SpawnAnimation>,
     mut sprite: Mut<Sprite>,
 ) {
-    if spawn_anim.animation.update(time.delta_seconds) {
+    if spawn_anim.animation.update(clock.animation_seconds) {
         // Updating the sprite size while the animation is not finished.
         let size = game_size.tile_size() * spawn_anim.animation.value();
         sprite.size.set_x(size);
@@ -1,11 +1,11 @@
 //! This module cotains the implementation of the SpawnTile event, reader and system.
 
-use crate::common::{GameSize, Position, Tile};
-use crate::movement::{Merged, Moving};
+use crate::common::{rules_board, GameEvent, GameRules, GameSize, Position, Tile};
+use crate::movement::Moving;
+use crate::theme::Theme;
 use bevy::prelude::*;
-use rand::Rng;
 
-use super::SpawnAnimation;
+use super::{tile_pattern::spawn_tile_pattern, tile_text::spawn_tile_text, SpawnAnimation};
 
 /// Event for spawning new tiles.
 pub struct SpawnTileEvent {
@@ -26,63 +26,39 @@ pub struct SpawnTileListener {
 }
 
 /// Spawning a new tile for every SpawnTileEvent event.
+/// The rules choose the position and the level of each tile.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_tiles(
     mut commands: Commands,
     game_size: Res<GameSize>,
+    rules: Res<GameRules>,
+    theme: Res<Theme>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut listener: ResMut<SpawnTileListener>,
     spawn_events: Res<Events<SpawnTileEvent>>,
-    positions: Query<With<Tile, &Position>>,
+    mut game_events: ResMut<Events<GameEvent>>,
+    tiles: Query<(&Tile, &Position)>,
 ) {
-    // Vector of empty tiles for all the iterations.
-    let mut free_pos = None;
+    // The board for all the iterations.
+    let mut board = None;
     for ev in listener.reader.iter(&spawn_events) {
         for _ in 0..ev.count {
-            if free_pos.is_none() {
-                // Creating vector of empty tiles.
-                let mut vec = Vec::new();
-                for row in 0..4 {
-                    for col in 0..4 {
-                        vec.push(Position { row, col });
-                    }
-                }
-
-                // Removing the existing tiles from the vector.
-                for pos in &mut positions.iter() {
-                    if let Some(idx) = vec.iter().position(|x| *x == *pos) {
-                        vec.remove(idx);
-                    }
-                }
-
-                free_pos = Some(vec);
-            }
-
-            let vec = free_pos.as_mut().unwrap();
+            let board = board.get_or_insert_with(|| rules_board(game_size.cells(), tiles.iter()));
 
             // Checking that the board is not full.
-            if vec.len() != 0 {
-                // Choosing a random empty tile.
-                let mut rng = rand::thread_rng();
-                let idx = rng.gen_range(0, vec.len());
-                let pos = vec.remove(idx);
+            if let Some((row, col, level)) = rules.spawn(board, &mut rand::thread_rng()) {
+                board.set(row, col, Some(level));
+
+                let pos = Position { row, col };
+                let tile = Tile { level };
 
-                // Choosing the new tile's level.
-                let tile = Tile {
-                    level: if rng.gen_bool(0.8) { 0 } else { 1 },
-                };
+                game_events.send(GameEvent::Spawned {
+                    position: pos,
+                    level: tile.level,
+                });
 
                 // Spawning the new tile.
-                commands
-                    .spawn(SpriteComponents {
-                        material: materials.add(tile.color().into()),
-                        transform: Transform::from_translation(pos.to_vec3(*game_size)),
-                        ..Default::default()
-                    })
-                    .with(tile)
-                    .with(pos)
-                    .with(SpawnAnimation::default())
-                    .with(Option::<Moving>::None)
-                    .with(Option::<Merged>::None);
+                spawn_tile(&mut commands, &mut materials, &theme, *game_size, tile, pos);
             } else {
                 #[cfg(debug_assertions)]
                 panic!("spawn_tiles(): Tried to spawn a tile when the board was full.")
@@ -90,3 +66,30 @@ pub fn spawn_tiles(
         }
     }
 }
+
+/// Spawns a tile entity with all of its components at a given position.
+/// The tile is spawned with a spawn animation, a text that writes its value and its pattern.
+pub fn spawn_tile(
+    commands: &mut Commands,
+    materials: &mut Assets<ColorMaterial>,
+    theme: &Theme,
+    game_size: GameSize,
+    tile: Tile,
+    position: Position,
+) {
+    commands
+        .spawn(SpriteComponents {
+            // Every tile has its own material, so its color can change when it's merged.
+            material: materials.add(theme.tile_color(tile.level).into()),
+            transform: Transform::from_translation(position.to_vec3(game_size)),
+            ..Default::default()
+        })
+        .with(tile)
+        .with(position)
+        .with(SpawnAnimation::default())
+        .with(Option::<Moving>::None);
+
+    let entity = commands.current_entity().unwrap();
+    spawn_tile_text(commands, theme, entity);
+    spawn_tile_pattern(commands, materials, entity);
+}
@@ -0,0 +1,165 @@
+//! This module contains the implementation of the patterns drawn on the tiles,
+//! so the levels can be told apart without their colors.
+
+use bevy::{prelude::*, render::texture::TextureFormat, sprite::SpriteResizeMode};
+
+use crate::{
+    common::{Settings, Tile},
+    theme::Theme,
+};
+
+/// The width and the height of a shape's texture in pixels.
+const SHAPE_PIXELS: usize = 32;
+
+/// The shapes of the patterns, each one checks if a point in `[-1, 1]²` is inside it.
+const SHAPES: [fn(f32, f32) -> bool; 6] = [
+    // Circle.
+    |x, y| x * x + y * y <= 0.81,
+    // Square.
+    |x, y| x.abs().max(y.abs()) <= 0.75,
+    // Triangle.
+    |x, y| y >= -0.75 && x.abs() <= (0.85 - y) * 0.55,
+    // Diamond.
+    |x, y| x.abs() + y.abs() <= 0.95,
+    // Plus.
+    |x, y| x.abs().max(y.abs()) <= 0.9 && x.abs().min(y.abs()) <= 0.3,
+    // Cross.
+    |x, y| x.abs().max(y.abs()) <= 0.8 && (x - y).abs().min((x + y).abs()) <= 0.4,
+];
+
+/// The size of a pattern and its offset from the tile's center, both relative to the tile's size.
+const PATTERN_SIZE: f32 = 0.25;
+const PATTERN_OFFSET: f32 = 0.3;
+
+/// This struct saves the textures of the patterns.
+/// The first patterns are the filled shapes, the others are their outlines.
+pub struct TilePatterns {
+    textures: Vec<Handle<Texture>>,
+}
+
+impl FromResources for TilePatterns {
+    fn from_resources(resources: &Resources) -> Self {
+        let mut textures = resources.get_mut::<Assets<Texture>>().unwrap();
+        let filled = SHAPES.iter().map(|&shape| shape_texture(shape));
+        // An outline is the shape without the shape shrunk inside it.
+        let outlined = SHAPES
+            .iter()
+            .map(|&shape| shape_texture(|x, y| shape(x, y) && !shape(x / 0.55, y / 0.55)));
+
+        Self {
+            textures: filled.chain(outlined).map(|t| textures.add(t)).collect(),
+        }
+    }
+}
+
+impl TilePatterns {
+    /// Returns the texture of a level's pattern, the patterns repeat after they run out.
+    fn texture(&self, level: u32) -> Handle<Texture> {
+        self.textures[level as usize % self.textures.len()].clone()
+    }
+}
+
+/// Draws a white shape on a transparent texture.
+/// Each pixel is sampled 4 times, so the edges are smooth.
+fn shape_texture(shape: impl Fn(f32, f32) -> bool) -> Texture {
+    let mut data = Vec::with_capacity(SHAPE_PIXELS * SHAPE_PIXELS * 4);
+    let to_unit =
+        |pixel: usize, offset: f32| ((pixel as f32 + offset) / SHAPE_PIXELS as f32) * 2.0 - 1.0;
+
+    for row in 0..SHAPE_PIXELS {
+        for col in 0..SHAPE_PIXELS {
+            let samples = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+                .iter()
+                // The texture's rows go from the top down.
+                .filter(|&&(dx, dy)| shape(to_unit(col, dx), -to_unit(row, dy)))
+                .count();
+            data.extend_from_slice(&[255, 255, 255, (samples * 255 / 4) as u8]);
+        }
+    }
+
+    Texture::new(
+        Vec2::new(SHAPE_PIXELS as f32, SHAPE_PIXELS as f32),
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// Component of the sprite that draws the pattern of a tile.
+pub struct TilePattern {
+    pub tile: Entity,
+}
+
+/// Spawns the pattern of a tile, its shape is chosen by `update_tile_patterns`.
+pub fn spawn_tile_pattern(
+    commands: &mut Commands,
+    materials: &mut Assets<ColorMaterial>,
+    tile: Entity,
+) {
+    commands
+        .spawn(SpriteComponents {
+            // Every pattern has its own material, so its shape can change when the tile is merged.
+            material: materials.add(ColorMaterial::default()),
+            sprite: Sprite {
+                size: Vec2::zero(),
+                resize_mode: SpriteResizeMode::Manual,
+            },
+            draw: Draw {
+                is_visible: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with(TilePattern { tile });
+}
+
+/// This system moves the patterns to their tiles' corners and chooses their shapes by
+/// the tiles' levels. The patterns are only shown when they are enabled in the settings.
+/// A pattern whose tile has been despawned is despawned too.
+#[allow(clippy::type_complexity)]
+pub fn update_tile_patterns(
+    mut commands: Commands,
+    (theme, settings, patterns): (Res<Theme>, Res<Settings>, Res<TilePatterns>),
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    tiles: Query<Without<TilePattern, (&Transform, &Sprite, Option<&Tile>)>>,
+    mut sprites: Query<(
+        Entity,
+        &TilePattern,
+        &Handle<ColorMaterial>,
+        &mut Transform,
+        &mut Sprite,
+        &mut Draw,
+    )>,
+) {
+    for (entity, pattern, handle, mut transform, mut sprite, mut draw) in sprites.iter_mut() {
+        let (tile_transform, tile_sprite, tile) = match tiles.get(pattern.tile) {
+            Ok(tile) => tile,
+            Err(_) => {
+                commands.despawn(entity);
+                continue;
+            }
+        };
+
+        if draw.is_visible != settings.tile_patterns {
+            draw.is_visible = settings.tile_patterns;
+        }
+
+        // The pattern follows the tile's size, so it's animated with the tile.
+        let size = tile_sprite.size.x();
+        let offset = Vec3::new(-PATTERN_OFFSET, PATTERN_OFFSET, 0.0) * size;
+        // The pattern is drawn over the tile.
+        transform.translation = tile_transform.translation + offset + Vec3::unit_z();
+        sprite.size = Vec2::new(size, size) * PATTERN_SIZE;
+
+        // A despawning tile keeps its last pattern.
+        if let Some(tile) = tile {
+            if let Some(material) = materials.get_mut(handle) {
+                let texture = Some(patterns.texture(tile.level));
+                let color = theme.tile_text_color(tile.level);
+                if material.texture != texture || material.color != color {
+                    material.texture = texture;
+                    material.color = color;
+                }
+            }
+        }
+    }
+}
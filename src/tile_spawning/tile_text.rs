@@ -0,0 +1,136 @@
+//! This module contains the implementation of the numbers written on the tiles.
+//! Texts can only be drawn by the ui, so each tile has a ui node that follows it
+//! around the board.
+
+use bevy::prelude::*;
+
+use crate::{
+    common::{AppState, GameState, Settings, Tile},
+    theme::Theme,
+    ui::{BindingsScreen, SettingsScreen},
+};
+
+/// Component of the ui node that writes the number of a tile.
+pub struct TileText {
+    pub tile: Entity,
+}
+
+/// Spawns the text of a tile, its value is written by `update_tile_texts`.
+pub fn spawn_tile_text(commands: &mut Commands, theme: &Theme, tile: Entity) {
+    commands
+        .spawn(NodeComponents {
+            style: Style {
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            draw: Draw {
+                is_visible: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with(TileText { tile })
+        .with_children(|parent| {
+            parent.spawn(TextComponents {
+                text: Text {
+                    font: theme.font_handle.clone(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        });
+}
+
+/// This system moves the texts over their tiles and writes the tiles' values.
+/// The texts are scaled with the tiles' sprites, so they follow the spawn, merge
+/// and despawn animations. A text whose tile has been despawned is despawned too.
+#[allow(clippy::too_many_arguments)]
+pub fn update_tile_texts(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    (theme, settings): (Res<Theme>, Res<Settings>),
+    (app_state, game_state): (Res<AppState>, Res<GameState>),
+    (bindings_screen, settings_screen): (Res<BindingsScreen>, Res<SettingsScreen>),
+    tiles: Query<(&Transform, &Sprite, Option<&Tile>)>,
+    mut nodes: Query<(Entity, &TileText, &mut Style, &Children)>,
+    mut texts: Query<(&mut Text, &mut Draw)>,
+) {
+    let window = windows.get_primary().unwrap();
+    // The ui's origin is at the bottom left corner, the camera's is at the center.
+    let origin = Vec2::new(window.width() as f32, window.height() as f32) / 2.0;
+
+    // The menus are drawn by the ui too, so the texts are hidden behind them.
+    let visible = *app_state != AppState::Menu
+        && !game_state.is_paused()
+        && !bindings_screen.open
+        && !settings_screen.open;
+
+    for (entity, tile_text, mut style, children) in nodes.iter_mut() {
+        let (transform, sprite, tile) = match tiles.get(tile_text.tile) {
+            Ok(tile) => tile,
+            Err(_) => {
+                commands.despawn_recursive(entity);
+                continue;
+            }
+        };
+
+        let size = sprite.size.x();
+        let corner = origin + transform.translation.truncate() - Vec2::new(size, size) / 2.0;
+        let position = Rect {
+            left: Val::Px(corner.x()),
+            // The ui's y axis points up, so the top is measured from the bottom.
+            top: Val::Px(corner.y()),
+            ..Default::default()
+        };
+        let node_size = Size::new(Val::Px(size), Val::Px(size));
+        // The style is only changed when needed, so the layout isn't computed every frame.
+        if style.position != position || style.size != node_size {
+            style.position = position;
+            style.size = node_size;
+        }
+
+        let (mut text, mut draw) = match texts.get_mut(children[0]) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+
+        if draw.is_visible != visible {
+            draw.is_visible = visible;
+        }
+
+        // A despawning tile keeps its last value.
+        if let Some(tile) = tile {
+            let value = settings.tile_numbers.format(tile.level);
+            if text.value != value {
+                text.value = value;
+            }
+
+            let color = theme.tile_text_color(tile.level);
+            if text.style.color != color {
+                text.style.color = color;
+            }
+        }
+
+        let font_size = font_size(&text.value, size);
+        if text.style.font_size != font_size {
+            text.style.font_size = font_size;
+        }
+        if text.font != theme.font_handle {
+            text.font = theme.font_handle.clone();
+        }
+    }
+}
+
+/// Calculates the font size of a number so it fits in a tile of the given size.
+fn font_size(value: &str, tile_size: f32) -> f32 {
+    let ratio = match value.chars().count() {
+        0..=2 => 0.55,
+        3 => 0.45,
+        4 => 0.36,
+        digits => 1.5 / digits as f32,
+    };
+
+    tile_size * ratio
+}
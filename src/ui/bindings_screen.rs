@@ -0,0 +1,318 @@
+//! This module contains the implementation of the bindings screen,
+//! where the player binds keys to the actions.
+
+use bevy::prelude::*;
+
+use crate::{
+    common::{Action, InputBindings, ACTIONS},
+    locale::{Locale, LocalizedText},
+    theme::{Theme, ThemeMaterials, ThemeText},
+};
+
+/// The key that opens and closes the screen, it can't be bound.
+pub const TOGGLE_KEY: KeyCode = KeyCode::F1;
+
+/// This plugin builds the bindings screen into the app.
+pub struct BindingsScreenPlugin;
+
+impl Plugin for BindingsScreenPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<BindingsScreen>()
+            .init_resource::<BindingsScreenMaterials>()
+            .add_system(toggle_bindings_screen.system())
+            .add_system(bindings_buttons.system())
+            .add_system(rebind_key.system())
+            .add_system(spawn_bindings_screen.system())
+            .add_system(update_bindings_texts.system());
+    }
+}
+
+/// This struct saves whether the screen is open and which action is being rebound.
+#[derive(Debug, Default)]
+pub struct BindingsScreen {
+    pub open: bool,
+    /// The action that the next pressed key is bound to.
+    listening: Option<Action>,
+    /// The message shown under the bindings, like a conflict between two bindings.
+    message: Option<LocalizedText>,
+}
+
+/// This struct saves the colors of the screen.
+pub struct BindingsScreenMaterials {
+    background: Handle<ColorMaterial>,
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    listening: Handle<ColorMaterial>,
+}
+
+impl FromResources for BindingsScreenMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
+        BindingsScreenMaterials {
+            background: theme_materials.dim.clone(),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            listening: theme_materials.button_pressed.clone(),
+        }
+    }
+}
+
+/// An identifier for the screen's root node.
+pub struct BindingsScreenNode;
+
+/// The buttons of the screen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BindingsButton {
+    /// Listens to the next key to bind it to the action.
+    Rebind(Action),
+    /// Restores the default bindings.
+    Reset,
+    Close,
+}
+
+/// The text of an action's button.
+pub struct BindingText(Action);
+
+/// The text of the screen's message.
+pub struct MessageText;
+
+/// Opens and closes the screen with F1.
+fn toggle_bindings_screen(keyboard: Res<Input<KeyCode>>, mut screen: ResMut<BindingsScreen>) {
+    if keyboard.just_pressed(TOGGLE_KEY) {
+        screen.open = !screen.open;
+    }
+}
+
+/// Handles the clicks on the screen's buttons.
+fn bindings_buttons(
+    mut screen: ResMut<BindingsScreen>,
+    mut bindings: ResMut<InputBindings>,
+    buttons: Query<(Mutated<Interaction>, &BindingsButton)>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if matches!(*interaction, Interaction::Clicked) {
+            match *button {
+                BindingsButton::Rebind(action) => {
+                    // Clicking the listening action again stops listening.
+                    if screen.listening == Some(action) {
+                        screen.listening = None;
+                        screen.message = None;
+                    } else {
+                        screen.listening = Some(action);
+                        screen.message = Some(
+                            LocalizedText::new("binding-listening")
+                                .with_message("action", action.name_key()),
+                        );
+                    }
+                }
+                BindingsButton::Reset => {
+                    *bindings = InputBindings::defaults();
+                    screen.listening = None;
+                    screen.message = Some(LocalizedText::new("binding-restored"));
+                }
+                BindingsButton::Close => screen.open = false,
+            }
+        }
+    }
+}
+
+/// Binds the pressed key to the listening action, unless it's bound to another action.
+fn rebind_key(
+    keyboard: Res<Input<KeyCode>>,
+    mut screen: ResMut<BindingsScreen>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    let action = match screen.listening {
+        Some(action) => action,
+        None => return,
+    };
+
+    let key = match keyboard.get_just_pressed().find(|&&key| key != TOGGLE_KEY) {
+        Some(&key) => key,
+        None => return,
+    };
+
+    match bindings.toggle(action, key) {
+        Ok(()) => {
+            screen.listening = None;
+            screen.message = None;
+        }
+        Err(conflict) => {
+            screen.message = Some(
+                LocalizedText::new("binding-conflict")
+                    .with_text("key", format!("{:?}", key))
+                    .with_message("action", conflict.name_key()),
+            );
+        }
+    }
+}
+
+/// Spawns the screen when it's opened, and despawns it when it's closed.
+fn spawn_bindings_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut screen: ResMut<BindingsScreen>,
+    materials: Res<BindingsScreenMaterials>,
+    nodes: Query<With<BindingsScreenNode, Entity>>,
+) {
+    let spawned = nodes.iter().next();
+
+    match (screen.open, spawned) {
+        (true, None) => {
+            // The texts are written by their `LocalizedText` or by `update_bindings_texts`.
+            let text = |font_size: f32| TextComponents {
+                text: Text {
+                    value: String::new(),
+                    font: theme.font_handle.clone(),
+                    style: TextStyle {
+                        font_size,
+                        color: theme.panel_text,
+                    },
+                },
+                ..Default::default()
+            };
+            let button = |width: f32| ButtonComponents {
+                style: Style {
+                    size: Size::new(Val::Px(width), Val::Px(40.0)),
+                    margin: Rect::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.normal.clone(),
+                ..Default::default()
+            };
+
+            commands
+                .spawn(NodeComponents {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        // The ui's y axis points up, so the children are reversed to be listed top down.
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    material: materials.background.clone(),
+                    ..Default::default()
+                })
+                .with(BindingsScreenNode)
+                .with_children(|parent| {
+                    parent
+                        .spawn(text(40.0))
+                        .with(LocalizedText::new("controls"))
+                        .with(ThemeText::Panel);
+
+                    for &action in ACTIONS.iter() {
+                        parent
+                            .spawn(button(420.0))
+                            .with(BindingsButton::Rebind(action))
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(text(25.0))
+                                    .with(BindingText(action))
+                                    .with(ThemeText::Panel);
+                            });
+                    }
+
+                    parent
+                        .spawn(text(20.0))
+                        .with(MessageText)
+                        .with(ThemeText::Panel);
+
+                    parent
+                        .spawn(NodeComponents {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                ..Default::default()
+                            },
+                            draw: Draw {
+                                is_visible: false,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(button(206.0))
+                                .with(BindingsButton::Reset)
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(text(25.0))
+                                        .with(LocalizedText::new("reset"))
+                                        .with(ThemeText::Panel);
+                                });
+                            parent
+                                .spawn(button(206.0))
+                                .with(BindingsButton::Close)
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(text(25.0))
+                                        .with(
+                                            LocalizedText::new("close-controls")
+                                                .with_text("key", format!("{:?}", TOGGLE_KEY)),
+                                        )
+                                        .with(ThemeText::Panel);
+                                });
+                        });
+                });
+        }
+        (false, Some(entity)) => {
+            commands.despawn_recursive(entity);
+            screen.listening = None;
+            screen.message = None;
+        }
+        _ => {}
+    }
+}
+
+/// Updates the texts of the screen to the current bindings,
+/// and colors the buttons.
+fn update_bindings_texts(
+    screen: Res<BindingsScreen>,
+    locale: Res<Locale>,
+    bindings: Res<InputBindings>,
+    materials: Res<BindingsScreenMaterials>,
+    mut texts: Query<(&mut Text, &BindingText)>,
+    mut messages: Query<With<MessageText, &mut Text>>,
+    mut buttons: Query<(&BindingsButton, &Interaction, &mut Handle<ColorMaterial>)>,
+) {
+    for (mut text, BindingText(action)) in texts.iter_mut() {
+        let value = locale.render(
+            &LocalizedText::new("binding")
+                .with_message("action", action.name_key())
+                .with_text("keys", bindings.key_names(*action, ", ")),
+        );
+
+        if text.value != value {
+            text.value = value;
+        }
+    }
+
+    let message = screen
+        .message
+        .as_ref()
+        .map(|message| locale.render(message))
+        .unwrap_or_default();
+    for mut text in messages.iter_mut() {
+        if text.value != message {
+            text.value = message.clone();
+        }
+    }
+
+    for (button, interaction, mut material) in buttons.iter_mut() {
+        // Highlighting the listening action.
+        let color = if screen.listening.map(BindingsButton::Rebind) == Some(*button) {
+            &materials.listening
+        } else if matches!(*interaction, Interaction::None) {
+            &materials.normal
+        } else {
+            &materials.hovered
+        };
+
+        if *material != *color {
+            *material = color.clone();
+        }
+    }
+}
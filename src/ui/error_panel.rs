@@ -0,0 +1,149 @@
+//! This module contains the implementation of the error panel, which shows
+//! errors that didn't stop the game, until the panel is clicked.
+
+use bevy::prelude::*;
+
+use crate::theme::{Theme, ThemeMaterials, ThemeText};
+
+/// The number of errors the panel shows.
+const SHOWN_ERRORS: usize = 4;
+
+/// Event for showing an error in the panel.
+pub struct ErrorEvent {
+    pub message: String,
+}
+
+/// Event listener for ErrorEvent.
+#[derive(Default)]
+pub struct ErrorListener {
+    pub reader: EventReader<ErrorEvent>,
+}
+
+/// The errors that are shown in the panel.
+#[derive(Default)]
+pub struct ShownErrors(Vec<String>);
+
+/// This plugin builds the error panel into the app.
+pub struct ErrorPanelPlugin;
+
+impl Plugin for ErrorPanelPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.add_event::<ErrorEvent>()
+            .init_resource::<ErrorListener>()
+            .init_resource::<ShownErrors>()
+            .add_startup_system(spawn_error_panel.system())
+            .add_system(show_errors.system())
+            .add_system(dismiss_errors.system());
+    }
+}
+
+/// An identifier for the error panel's node.
+pub struct ErrorPanel;
+
+/// An identifier for the error panel's text.
+pub struct ErrorText;
+
+/// Spawns the hidden error panel at the bottom of the screen.
+fn spawn_error_panel(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    theme_materials: Res<ThemeMaterials>,
+) {
+    let font_handle = theme.font_handle.clone();
+
+    commands
+        .spawn(ButtonComponents {
+            style: Style {
+                position_type: PositionType::Absolute,
+                // The ui's y axis points up, so `top` is the bottom of the screen.
+                position: Rect {
+                    left: Val::Percent(10.0),
+                    top: Val::Percent(2.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(80.0), Val::Px(30.0 * SHOWN_ERRORS as f32)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: theme_materials.error.clone(),
+            draw: Draw {
+                is_visible: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with(ErrorPanel)
+        .with_children(|parent| {
+            parent
+                .spawn(TextComponents {
+                    style: Style::default(),
+                    text: Text {
+                        value: String::new(),
+                        font: font_handle,
+                        style: TextStyle {
+                            font_size: 20.0,
+                            color: theme.panel_text,
+                        },
+                    },
+                    draw: Draw {
+                        is_visible: false,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with(ErrorText)
+                .with(ThemeText::Panel);
+        });
+}
+
+/// Adds the new errors to the panel and shows it.
+fn show_errors(
+    mut listener: ResMut<ErrorListener>,
+    error_events: Res<Events<ErrorEvent>>,
+    mut shown_errors: ResMut<ShownErrors>,
+    mut panels: Query<With<ErrorPanel, &mut Draw>>,
+    mut texts: Query<With<ErrorText, (&mut Text, &mut Draw)>>,
+) {
+    let mut changed = false;
+    for ev in listener.reader.iter(&error_events) {
+        eprintln!("{}", ev.message);
+        shown_errors.0.push(ev.message.clone());
+        changed = true;
+    }
+
+    if changed {
+        // Keeping only the latest errors.
+        let len = shown_errors.0.len();
+        if len > SHOWN_ERRORS {
+            shown_errors.0.drain(..len - SHOWN_ERRORS);
+        }
+
+        for mut draw in panels.iter_mut() {
+            draw.is_visible = true;
+        }
+
+        for (mut text, mut draw) in texts.iter_mut() {
+            text.value = shown_errors.0.join("\n");
+            draw.is_visible = true;
+        }
+    }
+}
+
+/// Hides the panel and clears its errors when it is clicked.
+fn dismiss_errors(
+    mut shown_errors: ResMut<ShownErrors>,
+    mut panels: Query<With<ErrorPanel, (Mutated<Interaction>, &mut Draw)>>,
+    mut texts: Query<With<ErrorText, &mut Draw>>,
+) {
+    for (interaction, mut draw) in panels.iter_mut() {
+        if draw.is_visible && matches!(*interaction, Interaction::Clicked) {
+            shown_errors.0.clear();
+            draw.is_visible = false;
+
+            for mut draw in texts.iter_mut() {
+                draw.is_visible = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! This module contains the implementation of the game over overlay,
+//! which sums up the game over the board.
+
+use bevy::prelude::*;
+use bevy_2048::easing::Easing;
+
+use crate::{
+    common::{Animation, AppState, GameSize, GameState, Tile},
+    locale::LocalizedText,
+    score::{GameStats, Score},
+    session::SessionEvent,
+    theme::{Theme, ThemeMaterials, ThemeText},
+};
+
+/// This plugin builds the game over overlay into the app.
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<GameOverMaterials>()
+            .add_system(spawn_game_over_overlay.system())
+            .add_system(layout_game_over_overlay.system())
+            .add_system(game_over_buttons.system());
+    }
+}
+
+/// This struct saves the colors of the overlay.
+pub struct GameOverMaterials {
+    background: Handle<ColorMaterial>,
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    pressed: Handle<ColorMaterial>,
+}
+
+impl FromResources for GameOverMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
+        GameOverMaterials {
+            background: theme_materials.overlay.clone(),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            pressed: theme_materials.button_pressed.clone(),
+        }
+    }
+}
+
+/// Component of the overlay's node, slides the overlay in over the board.
+pub struct GameOverOverlay {
+    animation: Animation,
+}
+
+impl Default for GameOverOverlay {
+    /// Sets the animation to drop the overlay and bounce it on the board in 0.6 seconds,
+    /// after the last move has been seen for 0.3 seconds.
+    fn default() -> Self {
+        Self {
+            animation: Animation::new(0.6, Easing::OutBounce).with_delay(0.3),
+        }
+    }
+}
+
+/// Component of the overlay's texts, the font size is `scale` times the board's size.
+pub struct OverlayText {
+    scale: f32,
+}
+
+/// The buttons of the overlay.
+/// There is no undo button, because moves can't be undone yet.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OverlayButton {
+    NewGame,
+    MainMenu,
+}
+
+impl OverlayButton {
+    /// Returns the key of the button's text.
+    fn text_key(self) -> &'static str {
+        match self {
+            Self::NewGame => "new-game",
+            Self::MainMenu => "main-menu",
+        }
+    }
+}
+
+/// Spawns the overlay when the game is over, and despawns it when a new game starts.
+#[allow(clippy::too_many_arguments)]
+fn spawn_game_over_overlay(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    app_state: Res<AppState>,
+    game_state: Res<GameState>,
+    game_size: Res<GameSize>,
+    score: Res<Score>,
+    stats: Res<GameStats>,
+    materials: Res<GameOverMaterials>,
+    tiles: Query<&Tile>,
+    overlays: Query<With<GameOverOverlay, Entity>>,
+) {
+    let game_over = *app_state == AppState::Playing && matches!(*game_state, GameState::GameOver);
+
+    match (game_over, overlays.iter().next()) {
+        (true, None) => {
+            let text = |localized: LocalizedText, scale: f32, theme_text: ThemeText| {
+                (
+                    TextComponents {
+                        text: Text {
+                            value: String::new(),
+                            font: theme.font_handle.clone(),
+                            style: TextStyle {
+                                font_size: game_size.board_size() * scale,
+                                color: theme.text_color(theme_text),
+                            },
+                        },
+                        ..Default::default()
+                    },
+                    OverlayText { scale },
+                    localized,
+                    theme_text,
+                )
+            };
+
+            let mut lines = vec![
+                LocalizedText::new("score").with_number("score", score.0),
+                LocalizedText::new("best-tile")
+                    .with_number("value", tiles.iter().map(Tile::score).max().unwrap_or(0)),
+                LocalizedText::new("moves").with_number("count", stats.moves),
+            ];
+            if stats.is_new_highscore(score.0) {
+                lines.push(LocalizedText::new("new-highscore"));
+            }
+
+            commands
+                .spawn(NodeComponents {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        // The ui's y axis points up, so the children are reversed to be listed top down.
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    material: materials.background.clone(),
+                    ..Default::default()
+                })
+                .with(GameOverOverlay::default())
+                .with_children(|parent| {
+                    let (components, scale, localized, theme_text) =
+                        text(LocalizedText::new("game-over"), 0.14, ThemeText::Text);
+                    parent
+                        .spawn(components)
+                        .with(scale)
+                        .with(localized)
+                        .with(theme_text);
+
+                    for line in lines {
+                        let (components, scale, localized, theme_text) =
+                            text(line, 0.07, ThemeText::Text);
+                        parent
+                            .spawn(components)
+                            .with(scale)
+                            .with(localized)
+                            .with(theme_text);
+                    }
+
+                    for &button in [OverlayButton::NewGame, OverlayButton::MainMenu].iter() {
+                        parent
+                            .spawn(ButtonComponents {
+                                style: Style {
+                                    size: Size::new(Val::Percent(60.0), Val::Percent(12.0)),
+                                    margin: Rect {
+                                        top: Val::Percent(3.0),
+                                        ..Default::default()
+                                    },
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..Default::default()
+                                },
+                                material: materials.normal.clone(),
+                                ..Default::default()
+                            })
+                            .with(button)
+                            .with_children(|parent| {
+                                let (components, scale, localized, theme_text) = text(
+                                    LocalizedText::new(button.text_key()),
+                                    0.07,
+                                    ThemeText::Panel,
+                                );
+                                parent
+                                    .spawn(components)
+                                    .with(scale)
+                                    .with(localized)
+                                    .with(theme_text);
+                            });
+                    }
+                });
+        }
+        (false, Some(entity)) => {
+            commands.despawn_recursive(entity);
+        }
+        _ => {}
+    }
+}
+
+/// Places the overlay over the board, sized by the game size, while sliding it in.
+fn layout_game_over_overlay(
+    time: Res<Time>,
+    windows: Res<Windows>,
+    game_size: Res<GameSize>,
+    mut overlays: Query<(&mut GameOverOverlay, &mut Style)>,
+    mut texts: Query<(&OverlayText, &mut Text)>,
+) {
+    let window = windows.get_primary().unwrap();
+    let (width, height) = (window.width() as f32, window.height() as f32);
+    let board_size = game_size.board_size();
+
+    for (mut overlay, mut style) in overlays.iter_mut() {
+        overlay.animation.update(time.delta_seconds);
+
+        // The ui's y axis points up, so `top` is the distance from the bottom of the screen.
+        // The overlay comes down from above the screen.
+        let centered = (height - board_size) / 2.0;
+        let top = centered + (1.0 - overlay.animation.value()) * (height - centered);
+
+        let size = Size::new(Val::Px(board_size), Val::Px(board_size));
+        let position = Rect {
+            left: Val::Px((width - board_size) / 2.0),
+            top: Val::Px(top),
+            ..Default::default()
+        };
+
+        // Changing the style only when needed, so the ui isn't laid out every frame.
+        if style.size != size || style.position != position {
+            style.size = size;
+            style.position = position;
+        }
+    }
+
+    for (text, mut value) in texts.iter_mut() {
+        let font_size = board_size * text.scale;
+        if (value.style.font_size - font_size).abs() > f32::EPSILON {
+            value.style.font_size = font_size;
+        }
+    }
+}
+
+/// Handles the clicks on the overlay's buttons and colors them.
+fn game_over_buttons(
+    mut game_state: ResMut<GameState>,
+    mut session_events: ResMut<Events<SessionEvent>>,
+    materials: Res<GameOverMaterials>,
+    mut buttons: Query<(
+        &OverlayButton,
+        Mutated<Interaction>,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    for (button, interaction, mut material) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *material = materials.pressed.clone();
+
+                match *button {
+                    OverlayButton::NewGame => *game_state = GameState::Restarting,
+                    OverlayButton::MainMenu => session_events.send(SessionEvent::Leave),
+                }
+            }
+            Interaction::Hovered => *material = materials.hovered.clone(),
+            Interaction::None => *material = materials.normal.clone(),
+        }
+    }
+}
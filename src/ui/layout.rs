@@ -0,0 +1,105 @@
+//! This module contains the implementation of the responsive layout.
+//! The side panels are restyled when the window switches between landscape and portrait,
+//! and the panels' texts are scaled with the window.
+
+use bevy::prelude::*;
+
+use crate::common::{GameSize, Layout};
+
+/// This plugin builds the responsive layout into the app.
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.add_system(apply_layout.system())
+            .add_system(scale_texts.system());
+    }
+}
+
+/// Component of a node that has a different style in each layout.
+#[derive(Debug, Clone)]
+pub struct ResponsiveStyle {
+    pub landscape: Style,
+    pub portrait: Style,
+}
+
+impl ResponsiveStyle {
+    /// Returns the style of a layout.
+    pub fn get(&self, layout: Layout) -> &Style {
+        match layout {
+            Layout::Landscape => &self.landscape,
+            Layout::Portrait => &self.portrait,
+        }
+    }
+
+    /// The style of a side node, a column on a side of the board in a landscape window,
+    /// and a row above or below it in a portrait window.
+    pub fn side_node() -> Self {
+        let landscape = Style {
+            size: Size::new(Val::Percent(20.0), Val::Percent(50.0)),
+            align_self: AlignSelf::FlexEnd,
+            align_items: AlignItems::FlexEnd,
+            flex_wrap: FlexWrap::Wrap,
+            ..Default::default()
+        };
+        let portrait = Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        };
+
+        Self {
+            landscape,
+            portrait,
+        }
+    }
+
+    /// The style of a panel in a side node, which takes `portrait_width` percent
+    /// of the row in a portrait window.
+    pub fn panel(landscape: Style, portrait_width: f32) -> Self {
+        let portrait = Style {
+            size: Size::new(Val::Percent(portrait_width), Val::Percent(80.0)),
+            margin: Rect::all(Val::Percent(1.5)),
+            ..landscape.clone()
+        };
+
+        Self {
+            landscape,
+            portrait,
+        }
+    }
+}
+
+/// Component of a text whose font size is scaled with the window.
+/// The size is the font size in the reference window.
+#[derive(Debug, Copy, Clone)]
+pub struct ScaledText(pub f32);
+
+impl ScaledText {
+    /// Returns the font size in the current window.
+    pub fn font_size(self, game_size: &GameSize) -> f32 {
+        self.0 * game_size.text_scale()
+    }
+}
+
+/// This system restyles the nodes when the layout changes.
+fn apply_layout(game_size: Res<GameSize>, mut nodes: Query<(&ResponsiveStyle, &mut Style)>) {
+    for (responsive, mut style) in nodes.iter_mut() {
+        let wanted = responsive.get(game_size.layout());
+        // Changing the style only when needed, so the ui isn't laid out every frame.
+        if *style != *wanted {
+            *style = wanted.clone();
+        }
+    }
+}
+
+/// This system scales the texts when the window is resized.
+fn scale_texts(game_size: Res<GameSize>, mut texts: Query<(&ScaledText, &mut Text)>) {
+    for (scaled, mut text) in texts.iter_mut() {
+        let font_size = scaled.font_size(&game_size);
+        if (text.style.font_size - font_size).abs() > f32::EPSILON {
+            text.style.font_size = font_size;
+        }
+    }
+}
@@ -6,9 +6,11 @@ mod score_texts;
 
 use bevy::prelude::*;
 
-use super::{RootNode, POST_ROOT_CREATION_STAGE};
+use super::{ResponsiveStyle, RootNode, POST_ROOT_CREATION_STAGE};
 use new_game_button::NewGameButtonMaterials;
 
+pub use score_texts::ScoreText;
+
 static POST_LS_CREATION_STAGE: &str = "POST-LEFT-SIDE-CREATION";
 
 /// This plugin builds the left side ui into the app.
@@ -35,22 +37,18 @@ pub struct LeftSideNode;
 
 /// Spawns the left side node as a child of the root node.
 fn spawn_left_side_node(mut commands: Commands, root_entity: Entity, _: &RootNode) {
+    let style = ResponsiveStyle::side_node();
     commands
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(20.0), Val::Percent(50.0)),
-                align_self: AlignSelf::FlexEnd,
-                align_items: AlignItems::FlexEnd,
-                flex_wrap: FlexWrap::Wrap,
-                ..Default::default()
-            },
+            style: style.landscape.clone(),
             draw: Draw {
                 is_visible: false,
                 ..Default::default()
             },
             ..Default::default()
         })
-        .with(LeftSideNode);
+        .with(LeftSideNode)
+        .with(style);
 
     // Making the left side node as a child of the root node.
     commands.push_children(root_entity, &[commands.current_entity().unwrap()]);
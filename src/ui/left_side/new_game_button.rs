@@ -3,7 +3,12 @@
 use bevy::prelude::*;
 
 use super::LeftSideNode;
-use crate::common::GameState;
+use crate::{
+    common::{AppState, GameState, Settings},
+    locale::LocalizedText,
+    theme::{Theme, ThemeMaterials, ThemeText},
+    ui::layout::{ResponsiveStyle, ScaledText},
+};
 
 /// This enum keeps information about the button state.
 pub enum NewGameButtonState {
@@ -37,18 +42,20 @@ pub struct NewGameButtonMaterials {
 
 impl FromResources for NewGameButtonMaterials {
     fn from_resources(resources: &Resources) -> Self {
-        let mut materials = resources.get_mut::<Assets<ColorMaterial>>().unwrap();
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
         NewGameButtonMaterials {
-            normal: materials.add(Color::rgb_u8(40, 40, 40).into()),
-            hovered: materials.add(Color::rgb_u8(64, 64, 64).into()),
-            pressed: materials.add(Color::rgb_u8(50, 50, 200).into()),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            pressed: theme_materials.button_pressed.clone(),
         }
     }
 }
 
 /// This system is responsible for the button's interaction.
 pub fn new_game_button_system(
+    app_state: Res<AppState>,
     mut game_state: ResMut<GameState>,
+    settings: Res<Settings>,
     button_materials: Res<NewGameButtonMaterials>,
     mut interaction_query: Query<(
         &Button,
@@ -68,8 +75,10 @@ pub fn new_game_button_system(
                 button_state.update_state();
 
                 // Restarting the game only when released on the button.
-                if matches!(*button_state, NewGameButtonState::Up) {
-                    *game_state = GameState::Restarting;
+                if matches!(*button_state, NewGameButtonState::Up)
+                    && *app_state == AppState::Playing
+                {
+                    game_state.restart(&settings);
                 }
             }
             Interaction::None => {
@@ -83,44 +92,53 @@ pub fn new_game_button_system(
 /// This system spawns the button at startup.
 pub fn spawn_new_game_button(
     mut commands: Commands,
-    assets: Res<AssetServer>,
+    theme: Res<Theme>,
     button_materials: Res<NewGameButtonMaterials>,
     ls_node_entity: Entity,
     _: &LeftSideNode,
 ) {
-    let font_handle = assets.get_handle("fonts/FiraSans-Bold.ttf");
+    let font_handle = theme.font_handle.clone();
 
+    let style = ResponsiveStyle::panel(
+        Style {
+            size: Size::new(Val::Percent(90.0), Val::Percent(28.0)),
+            margin: Rect {
+                left: Val::Percent(5.0),
+                top: Val::Percent(5.0),
+                right: Val::Percent(5.0),
+                bottom: Val::Px(0.0),
+            },
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        30.0,
+    );
     commands
         .spawn(ButtonComponents {
-            style: Style {
-                size: Size::new(Val::Percent(90.0), Val::Percent(28.0)),
-                margin: Rect {
-                    left: Val::Percent(5.0),
-                    top: Val::Percent(5.0),
-                    right: Val::Percent(5.0),
-                    bottom: Val::Px(0.0),
-                },
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..Default::default()
-            },
+            style: style.landscape.clone(),
             material: button_materials.normal.clone(),
             ..Default::default()
         })
+        .with(style)
         .with_children(|parent| {
             // Button text.
-            parent.spawn(TextComponents {
-                style: Style::default(),
-                text: Text {
-                    value: "New Game".to_string(),
-                    font: font_handle,
-                    style: TextStyle {
-                        font_size: 40.0,
-                        color: Color::WHITE,
+            parent
+                .spawn(TextComponents {
+                    style: Style::default(),
+                    text: Text {
+                        value: String::new(),
+                        font: font_handle,
+                        style: TextStyle {
+                            font_size: 40.0,
+                            color: theme.panel_text,
+                        },
                     },
-                },
-                ..Default::default()
-            });
+                    ..Default::default()
+                })
+                .with(LocalizedText::new("new-game"))
+                .with(ScaledText(40.0))
+                .with(ThemeText::Panel);
         })
         .with(NewGameButtonState::Idle);
 
@@ -1,101 +1,175 @@
 //! This module contains the implementation of the score and highscore texts.
 
 use bevy::prelude::*;
+use bevy_2048::easing::Easing;
 
 use super::LeftSideNode;
-use crate::{score::HighScore, score::Score};
+use crate::{
+    common::{Animation, GameClock},
+    locale::LocalizedText,
+    score::{HighScore, Score},
+    theme::{Theme, ThemeMaterials, ThemeText},
+    ui::layout::{ResponsiveStyle, ScaledText},
+};
 
-pub struct ScoreText;
+/// The seconds the score's text takes to count up to the score.
+const SCORE_COUNT_SECONDS: f32 = 0.4;
+
+/// Component of the score's text, which counts up to the score instead of jumping to it.
+pub struct ScoreText {
+    /// The score the count started from.
+    from: u32,
+    /// The score the text counts up to.
+    to: u32,
+    animation: Animation,
+}
+
+impl Default for ScoreText {
+    /// Sets the text to count from 0.
+    fn default() -> Self {
+        Self {
+            from: 0,
+            to: 0,
+            animation: Animation::new(SCORE_COUNT_SECONDS, Easing::OutCubic),
+        }
+    }
+}
+
+impl ScoreText {
+    /// Returns the score that is shown now.
+    fn shown(&self) -> u32 {
+        let counted = (self.to - self.from) as f32 * self.animation.value();
+        self.from + counted.round() as u32
+    }
+}
 
 pub struct HighScoreText;
 
-/// Updating the score text.
-pub fn score_text(score: Res<Score>, mut text: Mut<Text>, _: &ScoreText) {
-    text.value = format!("Score: {}", score.0)
+/// Updating the score text, counting up when the score grows.
+/// When the score goes down, like in a new game, the text jumps to it.
+/// The count follows the game's clock, so it pauses and speeds up with the animations.
+pub fn score_text(
+    clock: Res<GameClock>,
+    score: Res<Score>,
+    mut text: Mut<LocalizedText>,
+    mut score_text: Mut<ScoreText>,
+) {
+    if score.0 != score_text.to {
+        let shown = score_text.shown();
+        score_text.from = if score.0 > shown { shown } else { score.0 };
+        score_text.to = score.0;
+        score_text.animation = Animation::new(SCORE_COUNT_SECONDS, Easing::OutCubic);
+    }
+
+    if score_text.animation.update(clock.animation_seconds) {
+        let value = LocalizedText::new("score").with_number("score", score_text.shown());
+        if *text != value {
+            *text = value;
+        }
+    }
 }
 
 /// Updating the highscore text.
-pub fn highscore_text(highscore: Res<HighScore>, mut text: Mut<Text>, _: &HighScoreText) {
-    text.value = format!("Best: {}", highscore.0)
+pub fn highscore_text(highscore: Res<HighScore>, mut text: Mut<LocalizedText>, _: &HighScoreText) {
+    let value = LocalizedText::new("best").with_number("score", highscore.0);
+    if *text != value {
+        *text = value;
+    }
 }
 
 pub fn spawn_texts(
     mut commands: Commands,
-    assets: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    theme: Res<Theme>,
+    theme_materials: Res<ThemeMaterials>,
     ls_node_entity: Entity,
     _: &LeftSideNode,
 ) {
-    let font_handle = assets.get_handle("fonts/FiraSans-Bold.ttf");
+    let font_handle = theme.font_handle.clone();
 
     // Spawning score text.
+    let style = ResponsiveStyle::panel(
+        Style {
+            size: Size::new(Val::Percent(90.0), Val::Percent(28.0)),
+            margin: Rect::all(Val::Percent(5.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        30.0,
+    );
     commands
         // Base node.
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(90.0), Val::Percent(28.0)),
-                margin: Rect::all(Val::Percent(5.0)),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..Default::default()
-            },
-            material: materials.add(Color::rgb_u8(40, 40, 40).into()),
+            style: style.landscape.clone(),
+            material: theme_materials.panel.clone(),
             ..Default::default()
         })
+        .with(style)
         .with_children(|parent| {
             // Adding the text as a child.
             parent
                 .spawn(TextComponents {
                     style: Style::default(),
                     text: Text {
-                        value: "Score: 0".to_string(),
+                        value: String::new(),
                         font: font_handle.clone(),
                         style: TextStyle {
                             font_size: 40.0,
-                            color: Color::WHITE,
+                            color: theme.panel_text,
                         },
                     },
                     ..Default::default()
                 })
-                .with(ScoreText);
+                .with(ScoreText::default())
+                .with(ScaledText(40.0))
+                .with(LocalizedText::new("score").with_number("score", 0u32))
+                .with(ThemeText::Panel);
         });
     let score_entity = commands.current_entity().unwrap();
 
     // Spawning highscore text.
+    let style = ResponsiveStyle::panel(
+        Style {
+            size: Size::new(Val::Percent(90.0), Val::Percent(28.0)),
+            margin: Rect {
+                left: Val::Percent(5.0),
+                top: Val::Percent(5.0),
+                right: Val::Percent(5.0),
+                bottom: Val::Px(0.0),
+            },
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        30.0,
+    );
     commands
         // Base node.
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(90.0), Val::Percent(28.0)),
-                margin: Rect {
-                    left: Val::Percent(5.0),
-                    top: Val::Percent(5.0),
-                    right: Val::Percent(5.0),
-                    bottom: Val::Px(0.0),
-                },
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..Default::default()
-            },
-            material: materials.add(Color::rgb_u8(40, 40, 40).into()),
+            style: style.landscape.clone(),
+            material: theme_materials.panel.clone(),
             ..Default::default()
         })
+        .with(style)
         .with_children(|parent| {
             // Adding the text as a child.
             parent
                 .spawn(TextComponents {
                     style: Style::default(),
                     text: Text {
-                        value: "Best: 0".to_string(),
+                        value: String::new(),
                         font: font_handle.clone(),
                         style: TextStyle {
                             font_size: 40.0,
-                            color: Color::WHITE,
+                            color: theme.panel_text,
                         },
                     },
                     ..Default::default()
                 })
-                .with(HighScoreText);
+                .with(HighScoreText)
+                .with(ScaledText(40.0))
+                .with(LocalizedText::new("best").with_number("score", 0u32))
+                .with(ThemeText::Panel);
         });
     let highscore_entity = commands.current_entity().unwrap();
 
@@ -0,0 +1,398 @@
+//! This module contains the implementation of the main menu,
+//! where a new game is chosen or the left game is continued.
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_2048::rules;
+
+use super::SettingsScreen;
+use crate::{
+    common::{AppState, MAX_CELLS, MIN_CELLS},
+    locale::LocalizedText,
+    score::{HighScore, Statistics},
+    scripting::available_modes,
+    session::{LastGame, SessionEvent},
+    theme::{Theme, ThemeMaterials, ThemeText},
+};
+
+/// This plugin builds the main menu into the app.
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<MainMenu>()
+            .init_resource::<MainMenuMaterials>()
+            .add_system(main_menu_buttons.system())
+            .add_system(spawn_main_menu.system())
+            .add_system(update_main_menu_texts.system());
+    }
+}
+
+/// The pages of the menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MenuPage {
+    Main,
+    /// Chooses the mode and the board size of a new game.
+    NewGame,
+    Statistics,
+}
+
+/// This struct saves the shown page and the choices of the new game page.
+#[derive(Debug)]
+pub struct MainMenu {
+    page: MenuPage,
+    modes: Vec<String>,
+    mode: usize,
+    size: usize,
+}
+
+impl Default for MainMenu {
+    fn default() -> Self {
+        Self {
+            page: MenuPage::Main,
+            modes: available_modes(),
+            mode: 0,
+            size: rules::DEFAULT_SIZE,
+        }
+    }
+}
+
+/// This struct saves the colors of the menu.
+pub struct MainMenuMaterials {
+    background: Handle<ColorMaterial>,
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    pressed: Handle<ColorMaterial>,
+}
+
+impl FromResources for MainMenuMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
+        MainMenuMaterials {
+            background: theme_materials.background.clone(),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            pressed: theme_materials.button_pressed.clone(),
+        }
+    }
+}
+
+/// An identifier for the menu's root node and the page it shows.
+pub struct MainMenuNode(MenuPage);
+
+/// The buttons of the menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MainMenuButton {
+    Continue,
+    NewGame,
+    Statistics,
+    Settings,
+    Quit,
+    PreviousMode,
+    NextMode,
+    Smaller,
+    Larger,
+    Start,
+    Back,
+}
+
+impl MainMenuButton {
+    /// Returns the button's text, the arrows are the same in every language.
+    fn text(self) -> LocalizedText {
+        LocalizedText::new(match self {
+            Self::Continue => "continue",
+            Self::NewGame => "new-game",
+            Self::Statistics => "statistics",
+            Self::Settings => "settings",
+            Self::Quit => "quit",
+            Self::PreviousMode | Self::Smaller => "<",
+            Self::NextMode | Self::Larger => ">",
+            Self::Start => "start",
+            Self::Back => "back",
+        })
+    }
+}
+
+/// The texts of the new game page's choices.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChoiceText {
+    Mode,
+    Size,
+}
+
+/// Handles the clicks on the menu's buttons and colors them.
+#[allow(clippy::too_many_arguments)]
+fn main_menu_buttons(
+    mut menu: ResMut<MainMenu>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut session_events: ResMut<Events<SessionEvent>>,
+    mut app_exit_events: ResMut<Events<AppExit>>,
+    materials: Res<MainMenuMaterials>,
+    mut buttons: Query<(
+        Mutated<Interaction>,
+        &MainMenuButton,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    for (interaction, button, mut material) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *material = materials.pressed.clone();
+
+                match *button {
+                    MainMenuButton::Continue => session_events.send(SessionEvent::Continue),
+                    MainMenuButton::NewGame => menu.page = MenuPage::NewGame,
+                    MainMenuButton::Statistics => menu.page = MenuPage::Statistics,
+                    MainMenuButton::Settings => settings_screen.open = true,
+                    MainMenuButton::Quit => app_exit_events.send(AppExit),
+                    MainMenuButton::PreviousMode => {
+                        menu.mode = (menu.mode + menu.modes.len() - 1) % menu.modes.len();
+                    }
+                    MainMenuButton::NextMode => menu.mode = (menu.mode + 1) % menu.modes.len(),
+                    MainMenuButton::Smaller => menu.size = (menu.size - 1).max(MIN_CELLS),
+                    MainMenuButton::Larger => menu.size = (menu.size + 1).min(MAX_CELLS),
+                    MainMenuButton::Start => {
+                        session_events.send(SessionEvent::Start {
+                            mode: menu.modes[menu.mode].clone(),
+                            size: menu.size,
+                        });
+                        menu.page = MenuPage::Main;
+                    }
+                    MainMenuButton::Back => menu.page = MenuPage::Main,
+                }
+            }
+            Interaction::Hovered => *material = materials.hovered.clone(),
+            Interaction::None => *material = materials.normal.clone(),
+        }
+    }
+}
+
+/// Spawns the shown page while in the menu, and despawns it when the page changes
+/// or the game starts.
+#[allow(clippy::too_many_arguments)]
+fn spawn_main_menu(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    app_state: Res<AppState>,
+    menu: Res<MainMenu>,
+    last_game: Res<LastGame>,
+    statistics: Res<Statistics>,
+    highscore: Res<HighScore>,
+    materials: Res<MainMenuMaterials>,
+    nodes: Query<(Entity, &MainMenuNode)>,
+) {
+    let page = if *app_state == AppState::Menu {
+        Some(menu.page)
+    } else {
+        None
+    };
+
+    let spawned = nodes.iter().next();
+    if spawned.map(|(_, node)| node.0) == page {
+        return;
+    }
+
+    if let Some((entity, _)) = spawned {
+        commands.despawn_recursive(entity);
+    }
+
+    let page = match page {
+        Some(page) => page,
+        None => return,
+    };
+
+    let button = |width: f32| ButtonComponents {
+        style: Style {
+            size: Size::new(Val::Px(width), Val::Px(50.0)),
+            margin: Rect::all(Val::Px(6.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        material: materials.normal.clone(),
+        ..Default::default()
+    };
+    let row = || NodeComponents {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        draw: Draw {
+            is_visible: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    commands
+        .spawn(NodeComponents {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                // The ui's y axis points up, so the children are reversed to be listed top down.
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            // The menu hides the board.
+            material: materials.background.clone(),
+            ..Default::default()
+        })
+        .with(MainMenuNode(page))
+        .with_children(|parent| match page {
+            MenuPage::Main => {
+                spawn_text(parent, &theme, "2048", 100.0, ThemeText::Text);
+
+                let mut buttons = vec![
+                    MainMenuButton::NewGame,
+                    MainMenuButton::Statistics,
+                    MainMenuButton::Settings,
+                    MainMenuButton::Quit,
+                ];
+                if last_game.exists() {
+                    buttons.insert(0, MainMenuButton::Continue);
+                }
+
+                for button_type in buttons {
+                    parent
+                        .spawn(button(250.0))
+                        .with(button_type)
+                        .with_children(|parent| {
+                            spawn_text(parent, &theme, "", 35.0, ThemeText::Panel)
+                                .with(button_type.text());
+                        });
+                }
+            }
+            MenuPage::NewGame => {
+                spawn_text(parent, &theme, "", 60.0, ThemeText::Text)
+                    .with(LocalizedText::new("new-game"));
+
+                for &(label, previous, choice, next) in [
+                    (
+                        "mode",
+                        MainMenuButton::PreviousMode,
+                        ChoiceText::Mode,
+                        MainMenuButton::NextMode,
+                    ),
+                    (
+                        "board-size",
+                        MainMenuButton::Smaller,
+                        ChoiceText::Size,
+                        MainMenuButton::Larger,
+                    ),
+                ]
+                .iter()
+                {
+                    spawn_text(parent, &theme, "", 25.0, ThemeText::Text)
+                        .with(LocalizedText::new(label));
+                    parent.spawn(row()).with_children(|parent| {
+                        for &button_type in [previous, next].iter() {
+                            parent
+                                .spawn(button(50.0))
+                                .with(button_type)
+                                .with_children(|parent| {
+                                    spawn_text(parent, &theme, "", 35.0, ThemeText::Panel)
+                                        .with(button_type.text());
+                                });
+
+                            // The value is shown between the buttons.
+                            if button_type == previous {
+                                parent
+                                    .spawn(NodeComponents {
+                                        style: Style {
+                                            size: Size::new(Val::Px(200.0), Val::Px(50.0)),
+                                            justify_content: JustifyContent::Center,
+                                            align_items: AlignItems::Center,
+                                            ..Default::default()
+                                        },
+                                        draw: Draw {
+                                            is_visible: false,
+                                            ..Default::default()
+                                        },
+                                        ..Default::default()
+                                    })
+                                    .with_children(|parent| {
+                                        spawn_text(parent, &theme, "", 35.0, ThemeText::Text)
+                                            .with(choice);
+                                    });
+                            }
+                        }
+                    });
+                }
+
+                parent.spawn(row()).with_children(|parent| {
+                    for &button_type in [MainMenuButton::Back, MainMenuButton::Start].iter() {
+                        parent
+                            .spawn(button(150.0))
+                            .with(button_type)
+                            .with_children(|parent| {
+                                spawn_text(parent, &theme, "", 35.0, ThemeText::Panel)
+                                    .with(button_type.text());
+                            });
+                    }
+                });
+            }
+            MenuPage::Statistics => {
+                spawn_text(parent, &theme, "", 60.0, ThemeText::Text)
+                    .with(LocalizedText::new("statistics"));
+
+                for line in [
+                    LocalizedText::new("games-played")
+                        .with_number("count", statistics.games_played),
+                    LocalizedText::new("games-over").with_number("count", statistics.games_over),
+                    LocalizedText::new("moves").with_number("count", statistics.moves),
+                    LocalizedText::new("best-tile").with_number("value", statistics.best_tile),
+                    LocalizedText::new("highscore").with_number("score", highscore.0),
+                ]
+                .iter()
+                {
+                    spawn_text(parent, &theme, "", 30.0, ThemeText::Text).with(line.clone());
+                }
+
+                parent
+                    .spawn(button(250.0))
+                    .with(MainMenuButton::Back)
+                    .with_children(|parent| {
+                        spawn_text(parent, &theme, "", 35.0, ThemeText::Panel)
+                            .with(MainMenuButton::Back.text());
+                    });
+            }
+        });
+}
+
+/// Updates the texts of the new game page's choices.
+fn update_main_menu_texts(menu: Res<MainMenu>, mut texts: Query<(&ChoiceText, &mut Text)>) {
+    for (choice, mut text) in texts.iter_mut() {
+        let value = match choice {
+            ChoiceText::Mode => menu.modes[menu.mode].clone(),
+            ChoiceText::Size => format!("{0}x{0}", menu.size),
+        };
+
+        if text.value != value {
+            text.value = value;
+        }
+    }
+}
+
+/// Spawns a text with the font and the color of the theme as a child.
+fn spawn_text<'a, 'b>(
+    parent: &'b mut ChildBuilder<'a>,
+    theme: &Theme,
+    value: &str,
+    font_size: f32,
+    theme_text: ThemeText,
+) -> &'b mut ChildBuilder<'a> {
+    parent
+        .spawn(TextComponents {
+            text: Text {
+                value: value.to_string(),
+                font: theme.font_handle.clone(),
+                style: TextStyle {
+                    font_size,
+                    color: theme.text_color(theme_text),
+                },
+            },
+            ..Default::default()
+        })
+        .with(theme_text)
+}
@@ -1,9 +1,34 @@
+mod bindings_screen;
+mod error_panel;
+mod game_over;
+mod layout;
 mod left_side;
+mod main_menu;
+mod pause_menu;
+mod restart_dialog;
 mod right_side;
+mod score_popup;
+mod settings_screen;
+mod toast;
 
 use bevy::prelude::*;
+use bindings_screen::BindingsScreenPlugin;
+use error_panel::ErrorPanelPlugin;
+use game_over::GameOverPlugin;
+use layout::{LayoutPlugin, ResponsiveStyle};
 use left_side::LeftSidePlugin;
+use main_menu::MainMenuPlugin;
+use pause_menu::PauseMenuPlugin;
+use restart_dialog::RestartDialogPlugin;
 use right_side::RightSidePlugin;
+use score_popup::ScorePopupPlugin;
+use settings_screen::SettingsScreenPlugin;
+use toast::ToastPlugin;
+
+pub use bindings_screen::BindingsScreen;
+pub use error_panel::ErrorEvent;
+pub use settings_screen::SettingsScreen;
+pub use toast::ToastEvent;
 
 static ROOT_CREATION_STAGE: &str = "ROOT-CREATION";
 static POST_ROOT_CREATION_STAGE: &str = "POST-ROOT-CREATION";
@@ -16,32 +41,53 @@ impl Plugin for UiPlugin {
             .add_startup_stage_after(ROOT_CREATION_STAGE, POST_ROOT_CREATION_STAGE)
             .add_startup_system_to_stage(ROOT_CREATION_STAGE, create_root.system())
             // Should be added after the stages have been added.
+            .add_plugin(LayoutPlugin)
             .add_plugin(LeftSidePlugin)
-            .add_plugin(RightSidePlugin);
+            .add_plugin(RightSidePlugin)
+            .add_plugin(ScorePopupPlugin)
+            .add_plugin(ToastPlugin)
+            .add_plugin(ErrorPanelPlugin)
+            .add_plugin(SettingsScreenPlugin)
+            .add_plugin(BindingsScreenPlugin)
+            .add_plugin(MainMenuPlugin)
+            .add_plugin(PauseMenuPlugin)
+            .add_plugin(RestartDialogPlugin)
+            .add_plugin(GameOverPlugin);
     }
 }
 
 pub struct RootNode;
 
-fn create_root(mut commands: Commands, assets: Res<AssetServer>, mut _fonts: ResMut<Assets<Font>>) {
-    // Loading the font once.
-    assets.load::<Font, _>("fonts/FiraSans-Bold.ttf");
+/// The font is loaded by the theme.
+/// The side nodes are on the left and the right of the board in a landscape window,
+/// and above and below it in a portrait window.
+fn create_root(mut commands: Commands) {
+    let landscape = Style {
+        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+        justify_content: JustifyContent::SpaceBetween,
+        ..Default::default()
+    };
+    let portrait = Style {
+        // The ui's y axis points up, so the children are reversed to be listed top down.
+        flex_direction: FlexDirection::ColumnReverse,
+        ..landscape.clone()
+    };
 
     commands
         // ui camera
         .spawn(UiCameraComponents::default())
         // root node
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                justify_content: JustifyContent::SpaceBetween,
-                ..Default::default()
-            },
+            style: landscape.clone(),
             draw: Draw {
                 is_visible: false,
                 ..Default::default()
             },
             ..Default::default()
         })
-        .with(RootNode);
+        .with(RootNode)
+        .with(ResponsiveStyle {
+            landscape,
+            portrait,
+        });
 }
@@ -0,0 +1,213 @@
+//! This module contains the implementation of the pause menu.
+//! Bevy doesn't report when the window loses focus, so the game is paused by the pause key only.
+//! The pause key also cancels a restart that is being confirmed.
+
+use bevy::{app::AppExit, prelude::*};
+
+use super::{BindingsScreen, SettingsScreen};
+use crate::{
+    common::{Action, AppState, GameState, InputBindings, Settings},
+    locale::LocalizedText,
+    session::SessionEvent,
+    theme::{Theme, ThemeMaterials, ThemeText},
+};
+
+/// This plugin builds the pause menu into the app.
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<PauseMenuMaterials>()
+            .add_system(toggle_pause.system())
+            .add_system(pause_menu_buttons.system())
+            .add_system(spawn_pause_menu.system());
+    }
+}
+
+/// This struct saves the colors of the menu.
+pub struct PauseMenuMaterials {
+    background: Handle<ColorMaterial>,
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    pressed: Handle<ColorMaterial>,
+}
+
+impl FromResources for PauseMenuMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
+        PauseMenuMaterials {
+            background: theme_materials.dim.clone(),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            pressed: theme_materials.button_pressed.clone(),
+        }
+    }
+}
+
+/// An identifier for the menu's root node.
+pub struct PauseMenuNode;
+
+/// The buttons of the menu.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PauseMenuButton {
+    Resume,
+    Restart,
+    Settings,
+    MainMenu,
+    Quit,
+}
+
+impl PauseMenuButton {
+    /// Returns the key of the button's text.
+    fn text_key(self) -> &'static str {
+        match self {
+            Self::Resume => "resume",
+            Self::Restart => "restart",
+            Self::Settings => "settings",
+            Self::MainMenu => "main-menu",
+            Self::Quit => "quit",
+        }
+    }
+}
+
+/// Pauses and resumes the game when the pause key is pressed.
+fn toggle_pause(
+    app_state: Res<AppState>,
+    mut game_state: ResMut<GameState>,
+    keyboard: Res<Input<KeyCode>>,
+    bindings: Res<InputBindings>,
+    (bindings_screen, settings_screen): (Res<BindingsScreen>, Res<SettingsScreen>),
+) {
+    if *app_state != AppState::Playing
+        || bindings_screen.open
+        || settings_screen.open
+        || !bindings.just_pressed(Action::Pause, &keyboard)
+    {
+        return;
+    }
+
+    match *game_state {
+        GameState::Play => *game_state = GameState::Paused,
+        GameState::Paused => *game_state = GameState::Play,
+        GameState::ConfirmingRestart { paused: true } => *game_state = GameState::Paused,
+        GameState::ConfirmingRestart { paused: false } => *game_state = GameState::Play,
+        _ => {}
+    }
+}
+
+/// Handles the clicks on the menu's buttons and colors them.
+fn pause_menu_buttons(
+    mut game_state: ResMut<GameState>,
+    settings: Res<Settings>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut session_events: ResMut<Events<SessionEvent>>,
+    mut app_exit_events: ResMut<Events<AppExit>>,
+    materials: Res<PauseMenuMaterials>,
+    mut buttons: Query<(
+        Mutated<Interaction>,
+        &PauseMenuButton,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    for (interaction, button, mut material) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *material = materials.pressed.clone();
+
+                match *button {
+                    PauseMenuButton::Resume => *game_state = GameState::Play,
+                    PauseMenuButton::Restart => game_state.restart(&settings),
+                    PauseMenuButton::Settings => settings_screen.open = true,
+                    PauseMenuButton::MainMenu => session_events.send(SessionEvent::Leave),
+                    PauseMenuButton::Quit => app_exit_events.send(AppExit),
+                }
+            }
+            Interaction::Hovered => *material = materials.hovered.clone(),
+            Interaction::None => *material = materials.normal.clone(),
+        }
+    }
+}
+
+/// Spawns the menu when the game is paused, and despawns it when the game isn't paused.
+fn spawn_pause_menu(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    game_state: Res<GameState>,
+    materials: Res<PauseMenuMaterials>,
+    nodes: Query<With<PauseMenuNode, Entity>>,
+) {
+    let paused = matches!(*game_state, GameState::Paused);
+
+    match (paused, nodes.iter().next()) {
+        (true, None) => {
+            // The texts are written by their `LocalizedText`.
+            let text = |font_size: f32| TextComponents {
+                text: Text {
+                    value: String::new(),
+                    font: theme.font_handle.clone(),
+                    style: TextStyle {
+                        font_size,
+                        color: theme.panel_text,
+                    },
+                },
+                ..Default::default()
+            };
+
+            commands
+                .spawn(NodeComponents {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        // The ui's y axis points up, so the children are reversed to be listed top down.
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    material: materials.background.clone(),
+                    ..Default::default()
+                })
+                .with(PauseMenuNode)
+                .with_children(|parent| {
+                    parent
+                        .spawn(text(60.0))
+                        .with(LocalizedText::new("paused"))
+                        .with(ThemeText::Panel);
+
+                    for &button in [
+                        PauseMenuButton::Resume,
+                        PauseMenuButton::Restart,
+                        PauseMenuButton::Settings,
+                        PauseMenuButton::MainMenu,
+                        PauseMenuButton::Quit,
+                    ]
+                    .iter()
+                    {
+                        parent
+                            .spawn(ButtonComponents {
+                                style: Style {
+                                    size: Size::new(Val::Px(250.0), Val::Px(50.0)),
+                                    margin: Rect::all(Val::Px(6.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..Default::default()
+                                },
+                                material: materials.normal.clone(),
+                                ..Default::default()
+                            })
+                            .with(button)
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(text(35.0))
+                                    .with(LocalizedText::new(button.text_key()))
+                                    .with(ThemeText::Panel);
+                            });
+                    }
+                });
+        }
+        (false, Some(entity)) => {
+            commands.despawn_recursive(entity);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,171 @@
+//! This module contains the implementation of the dialog that confirms a restart,
+//! which is shown instead of restarting when `confirm_restart` is on in the settings.
+
+use bevy::prelude::*;
+
+use crate::{
+    common::GameState,
+    locale::LocalizedText,
+    theme::{Theme, ThemeMaterials, ThemeText},
+};
+
+/// This plugin builds the restart dialog into the app.
+pub struct RestartDialogPlugin;
+
+impl Plugin for RestartDialogPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<RestartDialogMaterials>()
+            .add_system(restart_dialog_buttons.system())
+            .add_system(spawn_restart_dialog.system());
+    }
+}
+
+/// This struct saves the colors of the dialog.
+pub struct RestartDialogMaterials {
+    background: Handle<ColorMaterial>,
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    pressed: Handle<ColorMaterial>,
+}
+
+impl FromResources for RestartDialogMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
+        RestartDialogMaterials {
+            background: theme_materials.dim.clone(),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            pressed: theme_materials.button_pressed.clone(),
+        }
+    }
+}
+
+/// An identifier for the dialog's root node.
+pub struct RestartDialogNode;
+
+/// The buttons of the dialog.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RestartDialogButton {
+    Restart,
+    Cancel,
+}
+
+impl RestartDialogButton {
+    /// Returns the key of the button's text.
+    fn text_key(self) -> &'static str {
+        match self {
+            Self::Restart => "restart",
+            Self::Cancel => "cancel",
+        }
+    }
+}
+
+/// Handles the clicks on the dialog's buttons and colors them.
+/// Cancelling goes back to the state the restart was asked from.
+fn restart_dialog_buttons(
+    mut game_state: ResMut<GameState>,
+    materials: Res<RestartDialogMaterials>,
+    mut buttons: Query<(
+        Mutated<Interaction>,
+        &RestartDialogButton,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    for (interaction, button, mut material) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *material = materials.pressed.clone();
+
+                let paused = match *game_state {
+                    GameState::ConfirmingRestart { paused } => paused,
+                    _ => continue,
+                };
+                *game_state = match *button {
+                    RestartDialogButton::Restart => GameState::Restarting,
+                    RestartDialogButton::Cancel if paused => GameState::Paused,
+                    RestartDialogButton::Cancel => GameState::Play,
+                };
+            }
+            Interaction::Hovered => *material = materials.hovered.clone(),
+            Interaction::None => *material = materials.normal.clone(),
+        }
+    }
+}
+
+/// Spawns the dialog when a restart is being confirmed, and despawns it afterwards.
+fn spawn_restart_dialog(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    game_state: Res<GameState>,
+    materials: Res<RestartDialogMaterials>,
+    nodes: Query<With<RestartDialogNode, Entity>>,
+) {
+    let confirming = matches!(*game_state, GameState::ConfirmingRestart { .. });
+
+    match (confirming, nodes.iter().next()) {
+        (true, None) => {
+            // The texts are written by their `LocalizedText`.
+            let text = |font_size: f32| TextComponents {
+                text: Text {
+                    value: String::new(),
+                    font: theme.font_handle.clone(),
+                    style: TextStyle {
+                        font_size,
+                        color: theme.panel_text,
+                    },
+                },
+                ..Default::default()
+            };
+
+            commands
+                .spawn(NodeComponents {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        // The ui's y axis points up, so the children are reversed to be listed top down.
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    material: materials.background.clone(),
+                    ..Default::default()
+                })
+                .with(RestartDialogNode)
+                .with_children(|parent| {
+                    parent
+                        .spawn(text(45.0))
+                        .with(LocalizedText::new("restart-confirm"))
+                        .with(ThemeText::Panel);
+
+                    for &button in
+                        [RestartDialogButton::Restart, RestartDialogButton::Cancel].iter()
+                    {
+                        parent
+                            .spawn(ButtonComponents {
+                                style: Style {
+                                    size: Size::new(Val::Px(250.0), Val::Px(50.0)),
+                                    margin: Rect::all(Val::Px(6.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..Default::default()
+                                },
+                                material: materials.normal.clone(),
+                                ..Default::default()
+                            })
+                            .with(button)
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(text(35.0))
+                                    .with(LocalizedText::new(button.text_key()))
+                                    .with(ThemeText::Panel);
+                            });
+                    }
+                });
+        }
+        (false, Some(entity)) => {
+            commands.despawn_recursive(entity);
+        }
+        _ => {}
+    }
+}
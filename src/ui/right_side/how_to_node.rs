@@ -2,58 +2,156 @@
 use bevy::prelude::*;
 
 use super::RightSideNode;
+use crate::{
+    common::{Action, GameSize, InputBindings},
+    locale::{Locale, LocalizedText},
+    theme::{Theme, ThemeMaterials, ThemeText},
+    ui::{
+        bindings_screen::TOGGLE_KEY,
+        layout::{ResponsiveStyle, ScaledText},
+    },
+};
 
-const EXPLANATION_TEXT: &str = r#"Use arrow keys or
-WASD keys to merge
-the tiles with the
-same color. Press
-SPACE to restart."#;
+/// The moving actions in the order their keys are listed, like WASD.
+const MOVE_ACTIONS: [Action; 4] = [
+    Action::MoveUp,
+    Action::MoveLeft,
+    Action::MoveDown,
+    Action::MoveRight,
+];
 
-/// Spawns the 'how to' text.
+/// Component of the 'how to' node, saves the text its lines are spawned from.
+/// The lines are spawned again when the language or the bindings change,
+/// since their number changes too.
+#[derive(Debug, Default)]
+pub struct HowToNode {
+    text: String,
+}
+
+/// Lists the moving keys, a line for each set of keys like the arrows or WASD.
+fn move_keys(bindings: &InputBindings) -> String {
+    let sets = MOVE_ACTIONS
+        .iter()
+        .map(|&action| bindings.keys(action).len())
+        .max()
+        .unwrap_or(0);
+    if sets == 0 {
+        return "-".to_string();
+    }
+
+    (0..sets)
+        .map(|set| {
+            MOVE_ACTIONS
+                .iter()
+                .map(|&action| {
+                    bindings
+                        .keys(action)
+                        .get(set)
+                        .map_or_else(|| "-".to_string(), |key| format!("{:?}", key))
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Spawns the 'how to' node, its lines are spawned by `update_how_to_text`.
 pub fn spawn_how_to_node(
     mut commands: Commands,
-    assets: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    theme_materials: Res<ThemeMaterials>,
     rs_node_entity: Entity,
     _: &RightSideNode,
 ) {
-    let font_handle = assets.get_handle("fonts/FiraSans-Bold.ttf");
-
+    let style = ResponsiveStyle::panel(
+        Style {
+            size: Size::new(Val::Percent(90.0), Val::Percent(70.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_wrap: FlexWrap::Wrap,
+            margin: Rect::all(Val::Percent(5.0)),
+            ..Default::default()
+        },
+        60.0,
+    );
     commands
         // Base node.
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(90.0), Val::Percent(70.0)),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_wrap: FlexWrap::Wrap,
-                margin: Rect::all(Val::Percent(5.0)),
-                ..Default::default()
-            },
-            material: materials.add(Color::rgb_u8(40, 40, 40).into()),
+            style: style.landscape.clone(),
+            material: theme_materials.panel.clone(),
             ..Default::default()
         })
-        .with_children(|parent| {
-            // Creates a new text for every line (in order to align the whole text to the middle).
-            for line in EXPLANATION_TEXT.lines().rev() {
-                spawn_text(parent, line, 25.0, font_handle.clone());
-            }
-
-            // Title.
-            spawn_text(parent, "How to play:", 40.0, font_handle);
-        });
+        .with(style)
+        .with(HowToNode::default());
 
     // Making 'how to' text as a child of the left side node.
     commands.push_children(rs_node_entity, &[commands.current_entity().unwrap()]);
 }
 
-/// Creates a text as a child of a given parent.
-fn spawn_text(parent: &mut ChildBuilder, text: &str, font_size: f32, font_handle: Handle<Font>) {
-    parent
+/// Spawns the lines of the 'how to' text in the chosen language with the bound keys,
+/// replacing the lines of the previous text.
+pub fn update_how_to_text(
+    mut commands: Commands,
+    (locale, bindings): (Res<Locale>, Res<InputBindings>),
+    (theme, game_size): (Res<Theme>, Res<GameSize>),
+    mut nodes: Query<(Entity, &mut HowToNode, Option<&Children>)>,
+) {
+    let text = locale.render(
+        &LocalizedText::new("how-to")
+            .with_text("move", move_keys(&bindings))
+            .with_text("restart", bindings.key_names(Action::Restart, "/"))
+            .with_text("controls", format!("{:?}", TOGGLE_KEY)),
+    );
+
+    for (entity, mut node, children) in nodes.iter_mut() {
+        if node.text == text {
+            continue;
+        }
+        node.text = text.clone();
+
+        for &child in children.iter().flat_map(|children| children.iter()) {
+            commands.despawn_recursive(child);
+        }
+
+        // Creates a new text for every line (in order to align the whole text to the middle).
+        let mut lines = Vec::new();
+        for line in text.lines().rev() {
+            lines.push(spawn_text(
+                &mut commands,
+                line,
+                ScaledText(25.0),
+                &theme,
+                &game_size,
+            ));
+        }
+
+        // Title.
+        lines.push(spawn_text(
+            &mut commands,
+            &locale.text("how-to-title"),
+            ScaledText(40.0),
+            &theme,
+            &game_size,
+        ));
+
+        commands.push_children(entity, &lines);
+    }
+}
+
+/// Creates a text and returns its node, to be made a child of the 'how to' node.
+/// The node's height fits the text, so it follows the text's scale.
+fn spawn_text(
+    commands: &mut Commands,
+    text: &str,
+    scaled: ScaledText,
+    theme: &Theme,
+    game_size: &GameSize,
+) -> Entity {
+    commands
         // Base node.
         .spawn(NodeComponents {
             style: Style {
-                size: Size::new(Val::Percent(100.0), Val::Px(font_size)),
+                size: Size::new(Val::Percent(100.0), Val::Auto),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..Default::default()
@@ -66,17 +164,22 @@ fn spawn_text(parent: &mut ChildBuilder, text: &str, font_size: f32, font_handle
         })
         .with_children(|parent| {
             // Adding the text as a child.
-            parent.spawn(TextComponents {
-                style: Style::default(),
-                text: Text {
-                    value: text.to_string(),
-                    font: font_handle,
-                    style: TextStyle {
-                        font_size,
-                        color: Color::WHITE,
+            parent
+                .spawn(TextComponents {
+                    style: Style::default(),
+                    text: Text {
+                        value: text.to_string(),
+                        font: theme.font_handle.clone(),
+                        style: TextStyle {
+                            font_size: scaled.font_size(game_size),
+                            color: theme.panel_text,
+                        },
                     },
-                },
-                ..Default::default()
-            });
+                    ..Default::default()
+                })
+                .with(scaled)
+                .with(ThemeText::Panel);
         });
+
+    commands.current_entity().unwrap()
 }
@@ -6,7 +6,7 @@ mod title;
 
 use bevy::prelude::*;
 
-use super::{RootNode, POST_ROOT_CREATION_STAGE};
+use super::{ResponsiveStyle, RootNode, POST_ROOT_CREATION_STAGE};
 
 pub struct RightSidePlugin;
 
@@ -21,7 +21,8 @@ impl Plugin for RightSidePlugin {
                 POST_RS_CREATION_STAGE,
                 how_to_node::spawn_how_to_node.system(),
             )
-            .add_startup_system_to_stage(POST_RS_CREATION_STAGE, title::spawn_title.system());
+            .add_startup_system_to_stage(POST_RS_CREATION_STAGE, title::spawn_title.system())
+            .add_system(how_to_node::update_how_to_text.system());
     }
 }
 
@@ -30,22 +31,18 @@ pub struct RightSideNode;
 
 /// Spawns the right side node as a child of the root node.
 fn spawn_right_side_node(mut commands: Commands, root_entity: Entity, _: &RootNode) {
+    let style = ResponsiveStyle::side_node();
     commands
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(20.0), Val::Percent(50.0)),
-                align_self: AlignSelf::FlexEnd,
-                align_items: AlignItems::FlexEnd,
-                flex_wrap: FlexWrap::Wrap,
-                ..Default::default()
-            },
+            style: style.landscape.clone(),
             draw: Draw {
                 is_visible: false,
                 ..Default::default()
             },
             ..Default::default()
         })
-        .with(RightSideNode);
+        .with(RightSideNode)
+        .with(style);
 
     // Making the right side node as a child of the root node.
     commands.push_children(root_entity, &[commands.current_entity().unwrap()]);
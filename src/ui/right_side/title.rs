@@ -3,50 +3,64 @@
 use bevy::prelude::*;
 
 use super::RightSideNode;
+use crate::{
+    locale::LocalizedText,
+    theme::{Theme, ThemeMaterials, ThemeText},
+    ui::layout::{ResponsiveStyle, ScaledText},
+};
 
 /// Spawns the title.
 pub fn spawn_title(
     mut commands: Commands,
-    assets: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    theme: Res<Theme>,
+    theme_materials: Res<ThemeMaterials>,
     rs_node_entity: Entity,
     _: &RightSideNode,
 ) {
-    let font_handle = assets.get_handle("fonts/FiraSans-Bold.ttf");
+    let font_handle = theme.font_handle.clone();
 
+    let style = ResponsiveStyle::panel(
+        Style {
+            size: Size::new(Val::Percent(90.0), Val::Percent(20.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_wrap: FlexWrap::Wrap,
+            margin: Rect {
+                left: Val::Percent(5.0),
+                top: Val::Percent(5.0),
+                right: Val::Percent(5.0),
+                bottom: Val::Px(0.0),
+            },
+            ..Default::default()
+        },
+        30.0,
+    );
     commands
         // Base node.
         .spawn(NodeComponents {
-            style: Style {
-                size: Size::new(Val::Percent(90.0), Val::Percent(20.0)),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_wrap: FlexWrap::Wrap,
-                margin: Rect {
-                    left: Val::Percent(5.0),
-                    top: Val::Percent(5.0),
-                    right: Val::Percent(5.0),
-                    bottom: Val::Px(0.0),
-                },
-                ..Default::default()
-            },
-            material: materials.add(Color::rgb_u8(40, 40, 40).into()),
+            style: style.landscape.clone(),
+            material: theme_materials.panel.clone(),
             ..Default::default()
         })
+        .with(style)
         .with_children(|parent| {
             // Adding the text as a child.
-            parent.spawn(TextComponents {
-                style: Style::default(),
-                text: Text {
-                    value: "Bevy 2048".to_string(),
-                    font: font_handle,
-                    style: TextStyle {
-                        font_size: 55.0,
-                        color: Color::WHITE,
+            parent
+                .spawn(TextComponents {
+                    style: Style::default(),
+                    text: Text {
+                        value: String::new(),
+                        font: font_handle,
+                        style: TextStyle {
+                            font_size: 55.0,
+                            color: theme.panel_text,
+                        },
                     },
-                },
-                ..Default::default()
-            });
+                    ..Default::default()
+                })
+                .with(LocalizedText::new("title"))
+                .with(ScaledText(55.0))
+                .with(ThemeText::Panel);
         });
 
     // Making the title as a chlid of the left side node.
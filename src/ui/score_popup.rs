@@ -0,0 +1,179 @@
+//! This module contains the implementation of the score popups, the "+N" texts that
+//! float up from the merged tiles and fade out. The score of a whole move is shown
+//! by a popup over the score panel too.
+
+use bevy::prelude::*;
+use bevy_2048::{easing::Easing, rules};
+
+use super::{layout::ScaledText, left_side::ScoreText, BindingsScreen, SettingsScreen};
+use crate::{
+    common::{Animation, AppState, GameClock, GameEvent, GameSize, GameState},
+    locale::{Locale, LocalizedText},
+    theme::Theme,
+};
+
+/// The size of a popup's node in pixels, the text is centered in it.
+const POPUP_SIZE: (f32, f32) = (200.0, 50.0);
+
+/// How high a popup floats, in pixels.
+const POPUP_RISE: f32 = 60.0;
+
+/// The popup of a move is shown this many pixels over the center of the score's text.
+const SCORE_PANEL_OFFSET: f32 = 40.0;
+
+/// Event listener for the game events that make popups.
+#[derive(Default)]
+pub struct ScorePopupListener {
+    pub reader: EventReader<GameEvent>,
+}
+
+/// This plugin builds the score popups into the app.
+pub struct ScorePopupPlugin;
+
+impl Plugin for ScorePopupPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<ScorePopupListener>()
+            .add_system(spawn_score_popups.system())
+            .add_system(update_score_popups.system());
+    }
+}
+
+/// Component of a popup's node.
+pub struct ScorePopup {
+    /// The center of the popup when it was spawned, in the ui's coordinates.
+    start: Vec2,
+    rise: Animation,
+    fade: Animation,
+}
+
+/// The popups' font size in the reference window.
+const POPUP_TEXT: ScaledText = ScaledText(30.0);
+
+/// Spawns a popup with its center at `start`.
+fn spawn_popup(
+    commands: &mut Commands,
+    theme: &Theme,
+    game_size: &GameSize,
+    start: Vec2,
+    value: &str,
+    color: Color,
+) {
+    commands
+        .spawn(NodeComponents {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Px(POPUP_SIZE.0), Val::Px(POPUP_SIZE.1)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            draw: Draw {
+                is_visible: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with(ScorePopup {
+            start,
+            rise: Animation::new(0.8, Easing::OutCubic),
+            fade: Animation::between(1.0, 0.0, 0.4, Easing::InCubic).with_delay(0.4),
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(TextComponents {
+                    text: Text {
+                        value: value.to_string(),
+                        font: theme.font_handle.clone(),
+                        style: TextStyle {
+                            font_size: POPUP_TEXT.font_size(game_size),
+                            color,
+                        },
+                    },
+                    ..Default::default()
+                })
+                .with(POPUP_TEXT);
+        });
+}
+
+/// This system spawns a popup over every merged tile, and a popup over the score panel
+/// with the score of the whole move.
+pub fn spawn_score_popups(
+    mut commands: Commands,
+    mut listener: ResMut<ScorePopupListener>,
+    game_events: Res<Events<GameEvent>>,
+    (windows, game_size, theme, locale): (Res<Windows>, Res<GameSize>, Res<Theme>, Res<Locale>),
+    score_texts: Query<With<ScoreText, &GlobalTransform>>,
+) {
+    let window = windows.get_primary().unwrap();
+    // The ui's origin is at the bottom left corner, the camera's is at the center.
+    let origin = Vec2::new(window.width() as f32, window.height() as f32) / 2.0;
+
+    // All the merges of a move are sent in the same frame.
+    let mut move_score: u32 = 0;
+    for event in listener.reader.iter(&game_events) {
+        if let GameEvent::Merged {
+            position,
+            level: Some(level),
+        } = event
+        {
+            let score = rules::tile_score(*level);
+            move_score = move_score.saturating_add(score);
+
+            let start = origin + position.to_vec3(*game_size).truncate();
+            let color = theme.tile_text_color(*level);
+            let value =
+                locale.render(&LocalizedText::new("score-gain").with_number("score", score));
+            spawn_popup(&mut commands, &theme, &game_size, start, &value, color);
+        }
+    }
+
+    if move_score > 0 {
+        if let Some(transform) = score_texts.iter().next() {
+            let start = transform.translation.truncate() + Vec2::new(0.0, SCORE_PANEL_OFFSET);
+            let value =
+                locale.render(&LocalizedText::new("score-gain").with_number("score", move_score));
+            spawn_popup(&mut commands, &theme, &game_size, start, &value, theme.text);
+        }
+    }
+}
+
+/// This system floats the popups up and fades them out, and despawns them when they are done.
+/// The popups are hidden behind the menus, and stop while the game is paused.
+pub fn update_score_popups(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    (app_state, game_state): (Res<AppState>, Res<GameState>),
+    (bindings_screen, settings_screen): (Res<BindingsScreen>, Res<SettingsScreen>),
+    mut popups: Query<(Entity, &mut ScorePopup, &mut Style, &Children)>,
+    mut texts: Query<(&mut Text, &mut Draw)>,
+) {
+    let visible = *app_state == AppState::Playing
+        && !game_state.is_paused()
+        && !bindings_screen.open
+        && !settings_screen.open;
+
+    for (entity, mut popup, mut style, children) in popups.iter_mut() {
+        popup.rise.update(clock.delta_seconds);
+        popup.fade.update(clock.delta_seconds);
+        if popup.fade.finished() {
+            commands.despawn_recursive(entity);
+            continue;
+        }
+
+        let corner = popup.start + Vec2::new(0.0, POPUP_RISE * popup.rise.value())
+            - Vec2::new(POPUP_SIZE.0, POPUP_SIZE.1) / 2.0;
+        style.position = Rect {
+            left: Val::Px(corner.x()),
+            // The ui's y axis points up, so the top is measured from the bottom.
+            top: Val::Px(corner.y()),
+            ..Default::default()
+        };
+
+        if let Ok((mut text, mut draw)) = texts.get_mut(children[0]) {
+            text.style.color.set_a(popup.fade.value());
+            if draw.is_visible != visible {
+                draw.is_visible = visible;
+            }
+        }
+    }
+}
@@ -0,0 +1,569 @@
+//! This module contains the implementation of the settings screen,
+//! where the player chooses the theme, how the tiles are written and drawn
+//! how fast they are animated, whether particles are shown, the audio's volumes,
+//! the language, whether the window is fullscreen and whether restarting asks
+//! for a confirmation, and opens the controls.
+//! The input's settings, like the depth of the input queue and how far a swipe
+//! should go, are on their own page.
+
+use bevy::prelude::*;
+
+use super::BindingsScreen;
+use crate::{
+    common::{step_volume, Settings, ANIMATION_SPEEDS, NUMBER_STYLES},
+    locale::{available_locales, Locale, LocalizedText},
+    movement::{GestureSettings, InputQueueSettings, MAX_QUEUE_DEPTH},
+    skin::{available_skins, Skin},
+    theme::{available_themes, Theme, ThemeMaterials, ThemeText},
+};
+
+/// This plugin builds the settings screen into the app.
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<SettingsScreen>()
+            .init_resource::<SettingsScreenMaterials>()
+            .add_system(settings_buttons.system())
+            .add_system(spawn_settings_screen.system())
+            .add_system(update_settings_texts.system());
+    }
+}
+
+/// This struct saves whether the screen is open and which page it shows.
+#[derive(Debug, Default)]
+pub struct SettingsScreen {
+    pub open: bool,
+    pub page: SettingsPage,
+}
+
+/// The pages of the screen, each lists its own choices.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum SettingsPage {
+    #[default]
+    General,
+    /// The settings of the input queue and the gestures.
+    Input,
+}
+
+impl SettingsPage {
+    /// Returns the choices of the page in the order they are listed.
+    fn choices(self) -> &'static [SettingsChoice] {
+        match self {
+            Self::General => &GENERAL_CHOICES,
+            Self::Input => &INPUT_CHOICES,
+        }
+    }
+
+    /// Returns the page the page's button switches to.
+    fn other(self) -> Self {
+        match self {
+            Self::General => Self::Input,
+            Self::Input => Self::General,
+        }
+    }
+
+    /// Returns the key of the page's name.
+    fn name_key(self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::Input => "input",
+        }
+    }
+}
+
+/// This struct saves the colors of the screen.
+pub struct SettingsScreenMaterials {
+    background: Handle<ColorMaterial>,
+    normal: Handle<ColorMaterial>,
+    hovered: Handle<ColorMaterial>,
+    pressed: Handle<ColorMaterial>,
+}
+
+impl FromResources for SettingsScreenMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let theme_materials = resources.get::<ThemeMaterials>().unwrap();
+        SettingsScreenMaterials {
+            background: theme_materials.dim.clone(),
+            normal: theme_materials.button.clone(),
+            hovered: theme_materials.button_hovered.clone(),
+            pressed: theme_materials.button_pressed.clone(),
+        }
+    }
+}
+
+/// An identifier for the screen's root node, with the page it shows.
+pub struct SettingsScreenNode(SettingsPage);
+
+/// The settings that are chosen by cycling through their values.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SettingsChoice {
+    Theme,
+    /// The images of the tiles, or none for flat colors.
+    Skin,
+    /// How the tiles' values are written.
+    Numbers,
+    /// Whether the tiles' patterns are drawn.
+    Patterns,
+    /// How fast the tiles are animated.
+    AnimationSpeed,
+    /// Whether the particles are shown.
+    Particles,
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    /// Whether all the audio is muted.
+    Mute,
+    /// The language of the texts.
+    Language,
+    /// Whether the window is fullscreen.
+    Fullscreen,
+    /// Whether restarting a game asks for a confirmation.
+    ConfirmRestart,
+    /// How many directions can wait while the tiles are moving.
+    QueueDepth,
+    /// Whether a direction that is the same as the last waiting one is dropped.
+    CollapseDuplicates,
+    /// Whether the moving animation is skipped while directions are waiting.
+    FastForward,
+    /// How far a drag or a swipe should go to move the tiles.
+    SwipeDistance,
+    /// How far from a direction a drag or a swipe can go.
+    SwipeAngle,
+}
+
+/// The choices of the general page.
+const GENERAL_CHOICES: [SettingsChoice; 13] = [
+    SettingsChoice::Theme,
+    SettingsChoice::Skin,
+    SettingsChoice::Numbers,
+    SettingsChoice::Patterns,
+    SettingsChoice::AnimationSpeed,
+    SettingsChoice::Particles,
+    SettingsChoice::MasterVolume,
+    SettingsChoice::SfxVolume,
+    SettingsChoice::MusicVolume,
+    SettingsChoice::Mute,
+    SettingsChoice::Language,
+    SettingsChoice::Fullscreen,
+    SettingsChoice::ConfirmRestart,
+];
+
+/// The choices of the input page.
+const INPUT_CHOICES: [SettingsChoice; 5] = [
+    SettingsChoice::QueueDepth,
+    SettingsChoice::CollapseDuplicates,
+    SettingsChoice::FastForward,
+    SettingsChoice::SwipeDistance,
+    SettingsChoice::SwipeAngle,
+];
+
+impl SettingsChoice {
+    /// Returns the key of the choice's label.
+    fn label_key(self) -> &'static str {
+        match self {
+            Self::Theme => "theme",
+            Self::Skin => "skin",
+            Self::Numbers => "tile-numbers",
+            Self::Patterns => "tile-patterns",
+            Self::AnimationSpeed => "animation-speed",
+            Self::Particles => "particles",
+            Self::MasterVolume => "volume",
+            Self::SfxVolume => "effects-volume",
+            Self::MusicVolume => "music-volume",
+            Self::Mute => "mute",
+            Self::Language => "language",
+            Self::Fullscreen => "fullscreen",
+            Self::ConfirmRestart => "confirm-restart",
+            Self::QueueDepth => "queue-depth",
+            Self::CollapseDuplicates => "collapse-duplicates",
+            Self::FastForward => "fast-forward",
+            Self::SwipeDistance => "swipe-distance",
+            Self::SwipeAngle => "swipe-angle",
+        }
+    }
+}
+
+/// The buttons of the screen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SettingsButton {
+    Previous(SettingsChoice),
+    Next(SettingsChoice),
+    /// Opens the bindings screen.
+    Controls,
+    /// Switches to a page.
+    Page(SettingsPage),
+    Close,
+}
+
+impl SettingsButton {
+    /// Returns the button's text, the arrows are the same in every language.
+    fn text(self) -> LocalizedText {
+        match self {
+            Self::Previous(_) => LocalizedText::new("<"),
+            Self::Next(_) => LocalizedText::new(">"),
+            Self::Controls => LocalizedText::new("controls"),
+            Self::Page(page) => LocalizedText::new(page.name_key()),
+            Self::Close => LocalizedText::new("close"),
+        }
+    }
+}
+
+/// The text of a choice's value.
+pub struct ChoiceText(SettingsChoice);
+
+/// Returns the index of the value before or after `current` in a list of `len` values.
+/// A value that isn't in the list starts the cycle from the first one.
+fn cycle(current: Option<usize>, len: usize, forward: bool) -> usize {
+    match current {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    }
+}
+
+/// Handles the clicks on the screen's buttons and colors them.
+/// The chosen settings are saved right away.
+fn settings_buttons(
+    mut screen: ResMut<SettingsScreen>,
+    mut bindings_screen: ResMut<BindingsScreen>,
+    mut settings: ResMut<Settings>,
+    (mut queue_settings, mut gesture_settings): (
+        ResMut<InputQueueSettings>,
+        ResMut<GestureSettings>,
+    ),
+    materials: Res<SettingsScreenMaterials>,
+    mut buttons: Query<(
+        Mutated<Interaction>,
+        &SettingsButton,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    for (interaction, button, mut material) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *material = materials.pressed.clone();
+
+                match *button {
+                    SettingsButton::Previous(choice) | SettingsButton::Next(choice) => {
+                        let forward = matches!(*button, SettingsButton::Next(_));
+                        match choice {
+                            SettingsChoice::Theme => {
+                                let themes = available_themes();
+                                if themes.is_empty() {
+                                    continue;
+                                }
+
+                                let current =
+                                    themes.iter().position(|name| *name == settings.theme);
+                                settings.theme =
+                                    themes[cycle(current, themes.len(), forward)].clone();
+                            }
+                            SettingsChoice::Skin => {
+                                // The first choice draws the tiles with flat colors.
+                                let skins = std::iter::once(None)
+                                    .chain(available_skins().into_iter().map(Some))
+                                    .collect::<Vec<_>>();
+                                let current = skins.iter().position(|skin| *skin == settings.skin);
+                                settings.skin = skins[cycle(current, skins.len(), forward)].clone();
+                            }
+                            SettingsChoice::Numbers => {
+                                let current = NUMBER_STYLES
+                                    .iter()
+                                    .position(|&style| style == settings.tile_numbers);
+                                settings.tile_numbers =
+                                    NUMBER_STYLES[cycle(current, NUMBER_STYLES.len(), forward)];
+                            }
+                            SettingsChoice::Patterns => {
+                                settings.tile_patterns = !settings.tile_patterns
+                            }
+                            SettingsChoice::AnimationSpeed => {
+                                let current = ANIMATION_SPEEDS
+                                    .iter()
+                                    .position(|&speed| speed == settings.animation_speed);
+                                settings.animation_speed = ANIMATION_SPEEDS
+                                    [cycle(current, ANIMATION_SPEEDS.len(), forward)];
+                            }
+                            SettingsChoice::Particles => settings.particles = !settings.particles,
+                            // The volumes are sliders, so they stop at their ends.
+                            SettingsChoice::MasterVolume => {
+                                settings.master_volume =
+                                    step_volume(settings.master_volume, forward)
+                            }
+                            SettingsChoice::SfxVolume => {
+                                settings.sfx_volume = step_volume(settings.sfx_volume, forward)
+                            }
+                            SettingsChoice::MusicVolume => {
+                                settings.music_volume = step_volume(settings.music_volume, forward)
+                            }
+                            SettingsChoice::Mute => settings.muted = !settings.muted,
+                            SettingsChoice::Language => {
+                                let locales = available_locales();
+                                if locales.is_empty() {
+                                    continue;
+                                }
+
+                                let current =
+                                    locales.iter().position(|name| *name == settings.language);
+                                settings.language =
+                                    locales[cycle(current, locales.len(), forward)].clone();
+                            }
+                            SettingsChoice::Fullscreen => {
+                                settings.window.fullscreen = !settings.window.fullscreen
+                            }
+                            SettingsChoice::ConfirmRestart => {
+                                settings.confirm_restart = !settings.confirm_restart
+                            }
+                            SettingsChoice::QueueDepth => {
+                                queue_settings.depth =
+                                    cycle(Some(queue_settings.depth), MAX_QUEUE_DEPTH + 1, forward)
+                            }
+                            SettingsChoice::CollapseDuplicates => {
+                                queue_settings.collapse_duplicates =
+                                    !queue_settings.collapse_duplicates
+                            }
+                            SettingsChoice::FastForward => {
+                                queue_settings.fast_forward = !queue_settings.fast_forward
+                            }
+                            SettingsChoice::SwipeDistance => {
+                                gesture_settings.step_min_distance(forward)
+                            }
+                            SettingsChoice::SwipeAngle => {
+                                gesture_settings.step_angle_tolerance(forward)
+                            }
+                        }
+                    }
+                    SettingsButton::Controls => bindings_screen.open = true,
+                    SettingsButton::Page(page) => screen.page = page,
+                    SettingsButton::Close => screen.open = false,
+                }
+            }
+            Interaction::Hovered => *material = materials.hovered.clone(),
+            Interaction::None => *material = materials.normal.clone(),
+        }
+    }
+}
+
+/// Spawns the screen when it's opened, and despawns it when it's closed.
+/// It's hidden while the bindings screen is open on top of it,
+/// and respawned when it switches to another page.
+fn spawn_settings_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    screen: Res<SettingsScreen>,
+    bindings_screen: Res<BindingsScreen>,
+    materials: Res<SettingsScreenMaterials>,
+    nodes: Query<(Entity, &SettingsScreenNode)>,
+) {
+    let open = screen.open && !bindings_screen.open;
+    let mut spawned = false;
+    for (entity, node) in nodes.iter() {
+        // Switching the page respawns the screen with the page's choices.
+        if open && node.0 == screen.page {
+            spawned = true;
+        } else {
+            commands.despawn_recursive(entity);
+        }
+    }
+
+    if open && !spawned {
+        // The texts are written by their `LocalizedText` or by `update_settings_texts`.
+        let text = |font_size: f32| TextComponents {
+            text: Text {
+                value: String::new(),
+                font: theme.font_handle.clone(),
+                style: TextStyle {
+                    font_size,
+                    color: theme.panel_text,
+                },
+            },
+            ..Default::default()
+        };
+        let button = |width: f32| ButtonComponents {
+            style: Style {
+                size: Size::new(Val::Px(width), Val::Px(32.0)),
+                margin: Rect::all(Val::Px(3.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.normal.clone(),
+            ..Default::default()
+        };
+
+        commands
+            .spawn(NodeComponents {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    // The ui's y axis points up, so the children are reversed to be listed top down.
+                    flex_direction: FlexDirection::ColumnReverse,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.background.clone(),
+                ..Default::default()
+            })
+            .with(SettingsScreenNode(screen.page))
+            .with_children(|parent| {
+                parent
+                    .spawn(text(40.0))
+                    .with(LocalizedText::new("settings"))
+                    .with(ThemeText::Panel);
+                // Each choice is a row with its label and its value between the buttons,
+                // so all the choices fit on the screen.
+                for &choice in screen.page.choices() {
+                    parent
+                        .spawn(NodeComponents {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            draw: Draw {
+                                is_visible: false,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(NodeComponents {
+                                    style: Style {
+                                        size: Size::new(Val::Px(220.0), Val::Px(32.0)),
+                                        align_items: AlignItems::Center,
+                                        ..Default::default()
+                                    },
+                                    draw: Draw {
+                                        is_visible: false,
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(text(25.0))
+                                        .with(LocalizedText::new(choice.label_key()))
+                                        .with(ThemeText::Panel);
+                                });
+                            parent
+                                .spawn(button(40.0))
+                                .with(SettingsButton::Previous(choice))
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(text(25.0))
+                                        .with(SettingsButton::Previous(choice).text())
+                                        .with(ThemeText::Panel);
+                                });
+                            parent
+                                .spawn(NodeComponents {
+                                    style: Style {
+                                        size: Size::new(Val::Px(200.0), Val::Px(32.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..Default::default()
+                                    },
+                                    draw: Draw {
+                                        is_visible: false,
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(text(25.0))
+                                        .with(ChoiceText(choice))
+                                        .with(ThemeText::Panel);
+                                });
+                            parent
+                                .spawn(button(40.0))
+                                .with(SettingsButton::Next(choice))
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(text(25.0))
+                                        .with(SettingsButton::Next(choice).text())
+                                        .with(ThemeText::Panel);
+                                });
+                        });
+                }
+
+                for &button_type in [
+                    SettingsButton::Controls,
+                    SettingsButton::Page(screen.page.other()),
+                    SettingsButton::Close,
+                ]
+                .iter()
+                {
+                    parent
+                        .spawn(button(516.0))
+                        .with(button_type)
+                        .with_children(|parent| {
+                            parent
+                                .spawn(text(25.0))
+                                .with(button_type.text())
+                                .with(ThemeText::Panel);
+                        });
+                }
+            });
+    }
+}
+
+/// Writes a volume as a percentage.
+fn percent(volume: f32) -> LocalizedText {
+    LocalizedText::new("percent").with_number("value", (volume * 100.0).round() as u32)
+}
+
+/// Writes whether a setting is on.
+fn on_off(on: bool) -> LocalizedText {
+    LocalizedText::new(if on { "on" } else { "off" })
+}
+
+/// Updates the texts of the choices, the theme, the skin and the language are written
+/// with the names of the loaded ones.
+fn update_settings_texts(
+    (theme, skin, locale): (Res<Theme>, Res<Skin>, Res<Locale>),
+    (settings, queue_settings, gesture_settings): (
+        Res<Settings>,
+        Res<InputQueueSettings>,
+        Res<GestureSettings>,
+    ),
+    mut texts: Query<(&ChoiceText, &mut Text)>,
+) {
+    for (ChoiceText(choice), mut text) in texts.iter_mut() {
+        let value = match choice {
+            SettingsChoice::Theme => theme.name.clone(),
+            // The flat colors are written in the player's language.
+            SettingsChoice::Skin if skin.id.is_none() => locale.text("none"),
+            SettingsChoice::Skin => skin.name.clone(),
+            SettingsChoice::Numbers => locale.text(settings.tile_numbers.name_key()),
+            SettingsChoice::Patterns => locale.render(&on_off(settings.tile_patterns)),
+            SettingsChoice::AnimationSpeed => locale.render(&settings.animation_speed.name()),
+            SettingsChoice::Particles => locale.render(&on_off(settings.particles)),
+            SettingsChoice::MasterVolume => locale.render(&percent(settings.master_volume)),
+            SettingsChoice::SfxVolume => locale.render(&percent(settings.sfx_volume)),
+            SettingsChoice::MusicVolume => locale.render(&percent(settings.music_volume)),
+            SettingsChoice::Mute => locale.render(&on_off(settings.muted)),
+            SettingsChoice::Language => locale.name.clone(),
+            SettingsChoice::Fullscreen => locale.render(&on_off(settings.window.fullscreen)),
+            SettingsChoice::ConfirmRestart => locale.render(&on_off(settings.confirm_restart)),
+            SettingsChoice::QueueDepth => locale.number(queue_settings.depth as u64),
+            SettingsChoice::CollapseDuplicates => {
+                locale.render(&on_off(queue_settings.collapse_duplicates))
+            }
+            SettingsChoice::FastForward => locale.render(&on_off(queue_settings.fast_forward)),
+            SettingsChoice::SwipeDistance => locale.render(
+                &LocalizedText::new("pixels")
+                    .with_number("value", gesture_settings.min_distance.round() as u32),
+            ),
+            SettingsChoice::SwipeAngle => locale.render(
+                &LocalizedText::new("degrees")
+                    .with_number("value", gesture_settings.angle_tolerance.round() as u32),
+            ),
+        };
+
+        if text.value != value {
+            text.value = value;
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! This module contains the implementation of the toast, a short message
+//! shown at the top of the screen.
+
+use bevy::prelude::*;
+
+use crate::theme::{Theme, ThemeMaterials, ThemeText};
+
+/// How long a toast is shown, in seconds.
+const TOAST_DURATION: f32 = 3.0;
+
+/// Event for showing a toast.
+pub struct ToastEvent {
+    pub message: String,
+}
+
+impl ToastEvent {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// Event listener for ToastEvent.
+#[derive(Default)]
+pub struct ToastListener {
+    pub reader: EventReader<ToastEvent>,
+}
+
+/// This plugin builds the toast into the app.
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.add_event::<ToastEvent>()
+            .init_resource::<ToastListener>()
+            .add_startup_system(spawn_toast.system())
+            .add_system(show_toasts.system());
+    }
+}
+
+/// Component of the toast's node, hides the toast when the timer is finished.
+pub struct Toast {
+    timer: Timer,
+}
+
+/// An identifier for the toast's text.
+pub struct ToastText;
+
+/// Spawns the hidden toast.
+fn spawn_toast(mut commands: Commands, theme: Res<Theme>, theme_materials: Res<ThemeMaterials>) {
+    let font_handle = theme.font_handle.clone();
+
+    let mut timer = Timer::from_seconds(TOAST_DURATION, false);
+    timer.finished = true;
+
+    commands
+        .spawn(NodeComponents {
+            style: Style {
+                position_type: PositionType::Absolute,
+                // The ui's y axis points up, so `bottom` is the top of the screen.
+                position: Rect {
+                    left: Val::Percent(25.0),
+                    bottom: Val::Percent(2.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(50.0), Val::Px(50.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: theme_materials.panel.clone(),
+            draw: Draw {
+                is_visible: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with(Toast { timer })
+        .with_children(|parent| {
+            parent
+                .spawn(TextComponents {
+                    style: Style::default(),
+                    text: Text {
+                        value: String::new(),
+                        font: font_handle,
+                        style: TextStyle {
+                            font_size: 25.0,
+                            color: theme.panel_text,
+                        },
+                    },
+                    draw: Draw {
+                        is_visible: false,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with(ToastText)
+                .with(ThemeText::Panel);
+        });
+}
+
+/// Shows the latest toast and hides it when its time is over.
+fn show_toasts(
+    time: Res<Time>,
+    mut listener: ResMut<ToastListener>,
+    toast_events: Res<Events<ToastEvent>>,
+    mut toasts: Query<(&mut Toast, &mut Draw)>,
+    mut texts: Query<With<ToastText, (&mut Text, &mut Draw)>>,
+) {
+    let message = listener
+        .reader
+        .latest(&toast_events)
+        .map(|ev| ev.message.clone());
+
+    for (mut toast, mut draw) in toasts.iter_mut() {
+        if message.is_some() {
+            toast.timer.reset();
+        } else {
+            toast.timer.tick(time.delta_seconds);
+        }
+
+        let visible = !toast.timer.finished;
+        draw.is_visible = visible;
+
+        for (mut text, mut text_draw) in texts.iter_mut() {
+            if let Some(message) = &message {
+                text.value = message.clone();
+            }
+            text_draw.is_visible = visible;
+        }
+    }
+}
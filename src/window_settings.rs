@@ -0,0 +1,110 @@
+//! This module contains the implementation of the window's settings.
+//! The window is created with the saved size and mode, it switches to fullscreen
+//! when it's chosen in the settings, and its size is remembered when it's resized.
+
+use bevy::{
+    prelude::*,
+    window::{WindowMode, WindowResized},
+};
+
+use crate::common::{Settings, SettingsChanged};
+
+/// This plugin builds the window's settings into the app.
+pub struct WindowSettingsPlugin;
+
+impl Plugin for WindowSettingsPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<WindowSettingsListener>()
+            .add_system(apply_window_settings.system())
+            .add_system(remember_window_size.system());
+    }
+}
+
+/// Creates the window's descriptor from the settings.
+pub fn window_descriptor(settings: &Settings) -> WindowDescriptor {
+    WindowDescriptor {
+        title: "Bevy 2048".to_string(),
+        width: settings.window.width,
+        height: settings.window.height,
+        mode: window_mode(settings.window.fullscreen),
+        ..Default::default()
+    }
+}
+
+fn window_mode(fullscreen: bool) -> WindowMode {
+    if fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    }
+}
+
+/// Event listener for SettingsChanged and WindowResized.
+#[derive(Default)]
+pub struct WindowSettingsListener {
+    pub settings_reader: EventReader<SettingsChanged>,
+    pub resized_reader: EventReader<WindowResized>,
+}
+
+/// This system switches the window's mode when the window's settings change.
+/// The window gets its saved size back when it leaves fullscreen.
+fn apply_window_settings(
+    mut listener: ResMut<WindowSettingsListener>,
+    settings_events: Res<Events<SettingsChanged>>,
+    settings: Res<Settings>,
+    mut windows: ResMut<Windows>,
+) {
+    let changed = listener
+        .settings_reader
+        .iter(&settings_events)
+        .any(|event| event.key == "window");
+    if !changed {
+        return;
+    }
+
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let fullscreen = matches!(window.mode(), WindowMode::BorderlessFullscreen);
+    if fullscreen != settings.window.fullscreen {
+        window.set_mode(window_mode(settings.window.fullscreen));
+        if !settings.window.fullscreen {
+            window.set_resolution(settings.window.width, settings.window.height);
+        }
+    }
+}
+
+/// This system saves the window's size into the settings when it's resized,
+/// unless the window is fullscreen.
+fn remember_window_size(
+    mut listener: ResMut<WindowSettingsListener>,
+    resized_events: Res<Events<WindowResized>>,
+    windows: Res<Windows>,
+    mut settings: ResMut<Settings>,
+) {
+    // Only the last size of the frame is kept.
+    let resized = listener
+        .resized_reader
+        .iter(&resized_events)
+        .rev()
+        .find(|event| event.id.is_primary());
+
+    let event = match resized {
+        Some(event) => event,
+        None => return,
+    };
+    let fullscreen = windows
+        .get_primary()
+        .is_none_or(|window| !matches!(window.mode(), WindowMode::Windowed));
+    if settings.window.fullscreen || fullscreen {
+        return;
+    }
+
+    let (width, height) = (event.width as u32, event.height as u32);
+    if settings.window.width != width || settings.window.height != height {
+        settings.window.width = width;
+        settings.window.height = height;
+    }
+}